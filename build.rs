@@ -1,8 +1,6 @@
-use spirv_builder::SpirvBuilder;
-use std::path::Path;
-
 fn main() {
-    SpirvBuilder::new(Path::new("shader"), "spirv-unknown-vulkan1.1")
+    #[cfg(feature = "gpu")]
+    spirv_builder::SpirvBuilder::new(std::path::Path::new("shader"), "spirv-unknown-vulkan1.1")
         .build()
         .expect("Shader failed to compile");
 }
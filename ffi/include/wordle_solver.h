@@ -0,0 +1,108 @@
+#ifndef WORDLE_SOLVER_H
+#define WORDLE_SOLVER_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Buffer size of a word or its tiles, 5 letters and the NUL terminator
+#define WORDLE_WORD_LEN 6
+
+typedef enum WordleStatus {
+  WORDLE_STATUS_OK = 0,
+  WORDLE_STATUS_NULL_POINTER,
+  WORDLE_STATUS_INVALID_UTF8,
+  WORDLE_STATUS_INVALID_DICTIONARY,
+  WORDLE_STATUS_INVALID_WORD,
+  WORDLE_STATUS_UNKNOWN_WORD,
+  WORDLE_STATUS_INVALID_FEEDBACK,
+  WORDLE_STATUS_BACKEND,
+  WORDLE_STATUS_NOTHING_TO_UNDO,
+  WORDLE_STATUS_PANIC,
+  WORDLE_STATUS_INVALID_STRATEGY,
+} WordleStatus;
+
+// Values of the `strategy` argument of `wordle_solver_new`, which takes a
+// plain integer so that an unknown value is an error and not undefined
+// behaviour
+typedef enum WordleStrategy {
+  WORDLE_STRATEGY_ENTROPY = 0,
+  WORDLE_STRATEGY_MINIMAX,
+  WORDLE_STRATEGY_EXPECTED_SIZE,
+  WORDLE_STRATEGY_MOST_BUCKETS,
+  WORDLE_STRATEGY_FREQUENCY,
+} WordleStrategy;
+
+typedef struct WordleDictionary WordleDictionary;
+
+typedef struct WordleSolver WordleSolver;
+
+// One ranked guess, `word` is NUL terminated
+typedef struct WordleSuggestion {
+  char word[WORDLE_WORD_LEN];
+  float score;
+} WordleSuggestion;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Static description of the status, never freed
+const char *wordle_status_message(enum WordleStatus status);
+
+// Word list the library is compiled with
+enum WordleStatus wordle_dictionary_bundled(struct WordleDictionary **out);
+
+// One word per line, NUL terminated
+enum WordleStatus wordle_dictionary_parse(const char *text, struct WordleDictionary **out);
+
+size_t wordle_dictionary_len(const struct WordleDictionary *dictionary);
+
+// Solvers created from the dictionary keep their own reference to it, so it
+// can be freed before them. NULL is a no-op.
+void wordle_dictionary_free(struct WordleDictionary *dictionary);
+
+// New game over the dictionary, the backend is picked the same way as by
+// the binary's `auto`. `strategy` is one of the `WordleStrategy` values.
+enum WordleStatus wordle_solver_new(const struct WordleDictionary *dictionary,
+                                    uint32_t strategy,
+                                    struct WordleSolver **out);
+
+// NULL is a no-op
+void wordle_solver_free(struct WordleSolver *solver);
+
+// Feedback of the guess, `GYB` letters (or the emoji) per tile
+enum WordleStatus wordle_solver_apply(struct WordleSolver *solver,
+                                      const char *guess,
+                                      const char *feedback);
+
+enum WordleStatus wordle_solver_undo(struct WordleSolver *solver);
+
+// Number of the words still consistent with the feedback
+size_t wordle_solver_candidate_count(const struct WordleSolver *solver);
+
+// Up to `len` remaining candidates into `out`, `written` is set to how many
+enum WordleStatus wordle_solver_candidates(const struct WordleSolver *solver,
+                                           char (*out)[WORDLE_WORD_LEN],
+                                           size_t len,
+                                           size_t *written);
+
+// `len` best next guesses into `out`, best first, `written` is set to how
+// many (fewer when the dictionary is smaller)
+enum WordleStatus wordle_solver_suggest(const struct WordleSolver *solver,
+                                        struct WordleSuggestion *out,
+                                        size_t len,
+                                        size_t *written);
+
+// Feedback of the guess to the answer as `GYB` tiles into `out`
+enum WordleStatus wordle_score(const char *guess, const char *answer, char (*out)[WORDLE_WORD_LEN]);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WORDLE_SOLVER_H */
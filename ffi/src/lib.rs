@@ -0,0 +1,459 @@
+//! C ABI of the solver, see `include/wordle_solver.h` (generated by
+//! cbindgen, `cbindgen --config cbindgen.toml --output include/wordle_solver.h`
+//! from this directory).
+//!
+//! Dictionaries and solvers are opaque handles owned by the caller and freed
+//! with their `_free` functions. Every fallible call returns a
+//! `WordleStatus`, results go to the caller's buffers. Panics are caught at
+//! the boundary and reported as `WORDLE_STATUS_PANIC`.
+//!
+//! Safety is the same for all of the functions: pointers are either NULL
+//! (reported as `WORDLE_STATUS_NULL_POINTER`) or valid, strings are NUL
+//! terminated, `out` buffers hold at least `len` elements and handles are
+//! not used after they are freed nor from two threads at once.
+
+// the contract is the same for every function, see above
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use wordle_solver::{BackendKind, Dictionary, Feedback, Solver, StrategyKind};
+
+/// Buffer size of a word or its tiles, 5 letters and the NUL terminator
+pub const WORDLE_WORD_LEN: usize = 6;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WordleStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidUtf8,
+    InvalidDictionary,
+    InvalidWord,
+    UnknownWord,
+    InvalidFeedback,
+    Backend,
+    NothingToUndo,
+    Panic,
+    InvalidStrategy,
+}
+
+/// Values of the `strategy` argument of `wordle_solver_new`, which takes a
+/// plain integer so that an unknown value is an error and not undefined
+/// behaviour
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WordleStrategy {
+    Entropy = 0,
+    Minimax,
+    ExpectedSize,
+    MostBuckets,
+    Frequency,
+}
+
+impl TryFrom<u32> for WordleStrategy {
+    type Error = WordleStatus;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => WordleStrategy::Entropy,
+            1 => WordleStrategy::Minimax,
+            2 => WordleStrategy::ExpectedSize,
+            3 => WordleStrategy::MostBuckets,
+            4 => WordleStrategy::Frequency,
+            _ => return Err(WordleStatus::InvalidStrategy),
+        })
+    }
+}
+
+impl From<WordleStrategy> for StrategyKind {
+    fn from(strategy: WordleStrategy) -> Self {
+        match strategy {
+            WordleStrategy::Entropy => StrategyKind::Entropy,
+            WordleStrategy::Minimax => StrategyKind::Minimax,
+            WordleStrategy::ExpectedSize => StrategyKind::ExpectedSize,
+            WordleStrategy::MostBuckets => StrategyKind::MostBuckets,
+            WordleStrategy::Frequency => StrategyKind::Frequency,
+        }
+    }
+}
+
+/// One ranked guess, `word` is NUL terminated
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WordleSuggestion {
+    pub word: [c_char; WORDLE_WORD_LEN],
+    pub score: f32,
+}
+
+pub struct WordleDictionary(Arc<Dictionary>);
+
+pub struct WordleSolver(Solver);
+
+/// Runs the body, turning a panic into `Panic` so that it never unwinds into C
+fn guard(f: impl FnOnce() -> Result<(), WordleStatus>) -> WordleStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => WordleStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => WordleStatus::Panic,
+    }
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, WordleStatus> {
+    if s.is_null() {
+        return Err(WordleStatus::NullPointer);
+    }
+
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| WordleStatus::InvalidUtf8)
+}
+
+unsafe fn ref_arg<'a, T>(ptr: *const T) -> Result<&'a T, WordleStatus> {
+    ptr.as_ref().ok_or(WordleStatus::NullPointer)
+}
+
+unsafe fn mut_arg<'a, T>(ptr: *mut T) -> Result<&'a mut T, WordleStatus> {
+    ptr.as_mut().ok_or(WordleStatus::NullPointer)
+}
+
+fn write_word(out: &mut [c_char; WORDLE_WORD_LEN], word: &str) {
+    for (o, b) in out.iter_mut().zip(word.bytes().chain(std::iter::once(0))) {
+        *o = b as c_char;
+    }
+}
+
+/// Static description of the status, never freed
+#[no_mangle]
+pub extern "C" fn wordle_status_message(status: WordleStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        WordleStatus::Ok => b"ok\0",
+        WordleStatus::NullPointer => b"null pointer argument\0",
+        WordleStatus::InvalidUtf8 => b"string is not valid UTF-8\0",
+        WordleStatus::InvalidDictionary => b"dictionary is malformed\0",
+        WordleStatus::InvalidWord => b"not a 5-letter lower-case word\0",
+        WordleStatus::UnknownWord => b"word is not in the dictionary\0",
+        WordleStatus::InvalidFeedback => b"feedback is not 5 G/Y/B tiles\0",
+        WordleStatus::Backend => b"backend is not available\0",
+        WordleStatus::NothingToUndo => b"no guess to undo\0",
+        WordleStatus::Panic => b"internal error\0",
+        WordleStatus::InvalidStrategy => b"not a WordleStrategy value\0",
+    };
+
+    message.as_ptr() as *const c_char
+}
+
+/// Word list the library is compiled with
+#[no_mangle]
+pub unsafe extern "C" fn wordle_dictionary_bundled(
+    out: *mut *mut WordleDictionary,
+) -> WordleStatus {
+    guard(|| {
+        let out = mut_arg(out)?;
+        *out = Box::into_raw(Box::new(WordleDictionary(Arc::new(Dictionary::bundled()))));
+
+        Ok(())
+    })
+}
+
+/// One word per line, NUL terminated
+#[no_mangle]
+pub unsafe extern "C" fn wordle_dictionary_parse(
+    text: *const c_char,
+    out: *mut *mut WordleDictionary,
+) -> WordleStatus {
+    guard(|| {
+        let out = mut_arg(out)?;
+        let dictionary =
+            Dictionary::parse(str_arg(text)?).map_err(|_| WordleStatus::InvalidDictionary)?;
+        *out = Box::into_raw(Box::new(WordleDictionary(Arc::new(dictionary))));
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wordle_dictionary_len(dictionary: *const WordleDictionary) -> usize {
+    dictionary.as_ref().map_or(0, |d| d.0.len())
+}
+
+/// Solvers created from the dictionary keep their own reference to it, so it
+/// can be freed before them. NULL is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_dictionary_free(dictionary: *mut WordleDictionary) {
+    if !dictionary.is_null() {
+        drop(Box::from_raw(dictionary));
+    }
+}
+
+/// New game over the dictionary, the backend is picked the same way as by
+/// the binary's `auto`. `strategy` is one of the `WordleStrategy` values.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_new(
+    dictionary: *const WordleDictionary,
+    strategy: u32,
+    out: *mut *mut WordleSolver,
+) -> WordleStatus {
+    guard(|| {
+        let dictionary = ref_arg(dictionary)?;
+        let out = mut_arg(out)?;
+        let strategy = WordleStrategy::try_from(strategy)?;
+        let backend = BackendKind::Auto
+            .build()
+            .map_err(|_| WordleStatus::Backend)?;
+        let solver = Solver::new(dictionary.0.clone(), backend)
+            .with_strategy(StrategyKind::from(strategy).build());
+        *out = Box::into_raw(Box::new(WordleSolver(solver)));
+
+        Ok(())
+    })
+}
+
+/// NULL is a no-op
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_free(solver: *mut WordleSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Feedback of the guess, `GYB` letters (or the emoji) per tile
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_apply(
+    solver: *mut WordleSolver,
+    guess: *const c_char,
+    feedback: *const c_char,
+) -> WordleStatus {
+    guard(|| {
+        let solver = mut_arg(solver)?;
+        let guess = str_arg(guess)?;
+        let feedback: Feedback = str_arg(feedback)?
+            .parse()
+            .map_err(|_| WordleStatus::InvalidFeedback)?;
+
+        solver
+            .0
+            .apply(guess, feedback)
+            .map_err(|_| WordleStatus::UnknownWord)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_undo(solver: *mut WordleSolver) -> WordleStatus {
+    guard(|| {
+        let solver = mut_arg(solver)?;
+        solver.0.undo().ok_or(WordleStatus::NothingToUndo)?;
+
+        Ok(())
+    })
+}
+
+/// Number of the words still consistent with the feedback
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_candidate_count(solver: *const WordleSolver) -> usize {
+    solver.as_ref().map_or(0, |s| s.0.candidates().len())
+}
+
+/// Up to `len` remaining candidates into `out`, `written` is set to how many
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_candidates(
+    solver: *const WordleSolver,
+    out: *mut [c_char; WORDLE_WORD_LEN],
+    len: usize,
+    written: *mut usize,
+) -> WordleStatus {
+    guard(|| {
+        let solver = ref_arg(solver)?;
+        let written = mut_arg(written)?;
+        if out.is_null() && len > 0 {
+            return Err(WordleStatus::NullPointer);
+        }
+
+        let candidates = solver.0.candidates();
+        let n = candidates.len().min(len);
+        for (i, &c) in candidates[..n].iter().enumerate() {
+            write_word(&mut *out.add(i), &solver.0.dictionary().word(c));
+        }
+        *written = n;
+
+        Ok(())
+    })
+}
+
+/// `len` best next guesses into `out`, best first, `written` is set to how
+/// many (fewer when the dictionary is smaller)
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_suggest(
+    solver: *const WordleSolver,
+    out: *mut WordleSuggestion,
+    len: usize,
+    written: *mut usize,
+) -> WordleStatus {
+    guard(|| {
+        let solver = ref_arg(solver)?;
+        let written = mut_arg(written)?;
+        if out.is_null() && len > 0 {
+            return Err(WordleStatus::NullPointer);
+        }
+
+        let top = solver.0.top(len);
+        for (i, &(idx, score)) in top.iter().enumerate() {
+            let suggestion = &mut *out.add(i);
+            write_word(&mut suggestion.word, &solver.0.dictionary().word(idx));
+            suggestion.score = score;
+        }
+        *written = top.len();
+
+        Ok(())
+    })
+}
+
+/// Feedback of the guess to the answer as `GYB` tiles into `out`
+#[no_mangle]
+pub unsafe extern "C" fn wordle_score(
+    guess: *const c_char,
+    answer: *const c_char,
+    out: *mut [c_char; WORDLE_WORD_LEN],
+) -> WordleStatus {
+    guard(|| {
+        let out = mut_arg(out)?;
+        let response = wordle_solver::api::ScoreResponse::new(str_arg(guess)?, str_arg(answer)?)
+            .map_err(|_| WordleStatus::InvalidWord)?;
+        write_word(out, &response.feedback.to_string());
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    use super::*;
+    use wordle_solver::fivegram::FIVEGRAM;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn word(w: &[c_char; WORDLE_WORD_LEN]) -> &str {
+        unsafe { CStr::from_ptr(w.as_ptr()) }.to_str().unwrap()
+    }
+
+    #[test]
+    fn game() {
+        unsafe {
+            let text = c("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares");
+            let mut dictionary = ptr::null_mut();
+            assert_eq!(
+                wordle_dictionary_parse(text.as_ptr(), &mut dictionary),
+                WordleStatus::Ok
+            );
+            assert_eq!(wordle_dictionary_len(dictionary), 7);
+
+            let mut solver = ptr::null_mut();
+            assert_eq!(
+                wordle_solver_new(dictionary, WordleStrategy::Minimax as u32, &mut solver),
+                WordleStatus::Ok
+            );
+            wordle_dictionary_free(dictionary);
+
+            let mut suggestions = [WordleSuggestion {
+                word: [0; WORDLE_WORD_LEN],
+                score: 0.0,
+            }; 10];
+            let mut written = 0;
+            assert_eq!(
+                wordle_solver_suggest(solver, suggestions.as_mut_ptr(), 10, &mut written),
+                WordleStatus::Ok
+            );
+            assert_eq!(written, 7);
+
+            assert_eq!(
+                wordle_solver_apply(solver, c("cigar").as_ptr(), c("BBBBB").as_ptr()),
+                WordleStatus::Ok
+            );
+            assert_eq!(wordle_solver_candidate_count(solver), 1);
+
+            let mut candidates = [[0; WORDLE_WORD_LEN]; 2];
+            assert_eq!(
+                wordle_solver_candidates(solver, candidates.as_mut_ptr(), 2, &mut written),
+                WordleStatus::Ok
+            );
+            assert_eq!((written, word(&candidates[0])), (1, "humph"));
+
+            assert_eq!(wordle_solver_undo(solver), WordleStatus::Ok);
+            assert_eq!(wordle_solver_undo(solver), WordleStatus::NothingToUndo);
+            assert_eq!(wordle_solver_candidate_count(solver), 7);
+
+            wordle_solver_free(solver);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        unsafe {
+            let mut dictionary = ptr::null_mut();
+            assert_eq!(
+                wordle_dictionary_parse(c("nope").as_ptr(), &mut dictionary),
+                WordleStatus::InvalidDictionary
+            );
+            assert_eq!(
+                wordle_dictionary_parse(ptr::null(), &mut dictionary),
+                WordleStatus::NullPointer
+            );
+
+            assert_eq!(
+                wordle_dictionary_parse(c("cigar").as_ptr(), &mut dictionary),
+                WordleStatus::Ok
+            );
+            let mut solver = ptr::null_mut();
+            assert_eq!(
+                wordle_solver_new(dictionary, 99, &mut solver),
+                WordleStatus::InvalidStrategy
+            );
+            assert!(solver.is_null());
+            wordle_solver_new(dictionary, WordleStrategy::Entropy as u32, &mut solver);
+            assert_eq!(
+                wordle_solver_apply(solver, c("zzzzz").as_ptr(), c("BBBBB").as_ptr()),
+                WordleStatus::UnknownWord
+            );
+            assert_eq!(
+                wordle_solver_apply(solver, c("cigar").as_ptr(), c("BBZBB").as_ptr()),
+                WordleStatus::InvalidFeedback
+            );
+            assert_eq!(
+                wordle_solver_undo(ptr::null_mut()),
+                WordleStatus::NullPointer
+            );
+
+            let mut tiles = [0; WORDLE_WORD_LEN];
+            assert_eq!(
+                wordle_score(c("tares").as_ptr(), c("sorel").as_ptr(), &mut tiles),
+                WordleStatus::Ok
+            );
+            assert_eq!(word(&tiles), "BBGGY");
+
+            wordle_solver_free(solver);
+            wordle_dictionary_free(dictionary);
+        }
+    }
+
+    #[test]
+    fn word_len() {
+        assert_eq!(WORDLE_WORD_LEN, FIVEGRAM + 1);
+    }
+
+    #[test]
+    fn panics_become_status() {
+        assert_eq!(guard(|| panic!("boom")), WordleStatus::Panic);
+        let message = unsafe { CStr::from_ptr(wordle_status_message(WordleStatus::Panic)) };
+        assert_eq!(message.to_str().unwrap(), "internal error");
+    }
+}
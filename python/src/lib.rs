@@ -0,0 +1,402 @@
+//! Python module `wordle`, for exploring strategies from notebooks.
+//!
+//! Histograms and entropies go through the same backends (rayon + SIMD) as
+//! the binary and come back as NumPy arrays, indices into the `Dictionary`
+//! are used everywhere in place of the words.
+//!
+//! ```python
+//! import wordle
+//! d = wordle.Dictionary.bundled()
+//! left = wordle.filter(d, "tares", "BBGGY")
+//! e = wordle.entropies(d, candidates=left)
+//! d[e.argmax()]
+//! ```
+
+use std::sync::Arc;
+
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use serde::de::value::{Error as DeError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+
+use wordle_solver::api::ScoreResponse;
+use wordle_solver::pattern::PATTERN_COUNT;
+use wordle_solver::simulate::simulate as simulate_games;
+use wordle_solver::{BackendKind, Dictionary, EntropyBackend, Feedback, Solver, StrategyKind};
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+#[pyclass(name = "Dictionary")]
+#[derive(Clone)]
+struct PyDictionary {
+    inner: Arc<Dictionary>,
+}
+
+#[pymethods]
+impl PyDictionary {
+    /**
+        Word list the solver is compiled with, answers first
+    **/
+    #[staticmethod]
+    fn bundled() -> Self {
+        PyDictionary {
+            inner: Arc::new(Dictionary::bundled()),
+        }
+    }
+
+    /**
+        One word per line
+    **/
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        let dictionary = Dictionary::parse(text).map_err(value_error)?;
+
+        Ok(PyDictionary {
+            inner: Arc::new(dictionary),
+        })
+    }
+
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn words(&self) -> Vec<String> {
+        (0..self.inner.len()).map(|i| self.inner.word(i)).collect()
+    }
+
+    fn index(&self, word: &str) -> PyResult<usize> {
+        self.inner
+            .index_of(word)
+            .ok_or_else(|| value_error(format!("`{}` is not in the dictionary", word)))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __getitem__(&self, idx: usize) -> PyResult<String> {
+        if idx < self.inner.len() {
+            Ok(self.inner.word(idx))
+        } else {
+            Err(PyIndexError::new_err(idx))
+        }
+    }
+}
+
+fn backend(name: &str) -> PyResult<Arc<dyn EntropyBackend>> {
+    let kind = match name {
+        "auto" => BackendKind::Auto,
+        "cpu" => BackendKind::Cpu,
+        "simd" => BackendKind::Simd,
+        "transposed" => BackendKind::Transposed,
+        "gpu" => BackendKind::Gpu,
+        _ => return Err(value_error(format!("unknown backend `{}`", name))),
+    };
+
+    kind.build().map_err(value_error)
+}
+
+fn strategy(name: &str) -> PyResult<StrategyKind> {
+    let name: StrDeserializer<DeError> = name.into_deserializer();
+
+    StrategyKind::deserialize(name).map_err(value_error)
+}
+
+/**
+    Indices of the array checked against the dictionary, all of the words
+    when there's no array
+**/
+fn indices(dictionary: &Dictionary, idx: Option<PyReadonlyArray1<i64>>) -> PyResult<Vec<usize>> {
+    let idx = match idx {
+        Some(idx) => idx,
+        None => return Ok((0..dictionary.len()).collect()),
+    };
+
+    idx.as_array()
+        .iter()
+        .map(|&i| match usize::try_from(i) {
+            Ok(i) if i < dictionary.len() => Ok(i),
+            _ => Err(PyIndexError::new_err(i)),
+        })
+        .collect()
+}
+
+/**
+    Feedback of the guess to the answer, `G`reen, `Y`ellow and `B`lack tiles
+**/
+#[pyfunction]
+fn score(guess: &str, answer: &str) -> PyResult<String> {
+    let response = ScoreResponse::new(guess, answer).map_err(value_error)?;
+
+    Ok(response.feedback.to_string())
+}
+
+/**
+    Row major feedback indices of the guesses against the answers
+**/
+fn score_indices(dictionary: &Dictionary, guesses: &[usize], answers: &[usize]) -> Vec<u8> {
+    let bytes = dictionary.bytes();
+
+    guesses
+        .par_iter()
+        .flat_map_iter(|&g| {
+            answers
+                .iter()
+                .map(move |&a| Feedback::score(&bytes[g], &bytes[a]).index() as u8)
+        })
+        .collect()
+}
+
+/**
+    Feedback index (0 is solved) of every guess against every answer
+**/
+#[pyfunction(guesses = "None", answers = "None")]
+fn score_matrix<'py>(
+    py: Python<'py>,
+    dictionary: &PyDictionary,
+    guesses: Option<PyReadonlyArray1<i64>>,
+    answers: Option<PyReadonlyArray1<i64>>,
+) -> PyResult<&'py PyArray2<u8>> {
+    let dictionary = &dictionary.inner;
+    let guesses = indices(dictionary, guesses)?;
+    let answers = indices(dictionary, answers)?;
+
+    let matrix = py.allow_threads(|| score_indices(dictionary, &guesses, &answers));
+
+    matrix
+        .into_pyarray(py)
+        .reshape([guesses.len(), answers.len()])
+}
+
+/**
+    Candidates (all of the words by default) consistent with the feedback
+**/
+#[pyfunction(candidates = "None")]
+fn filter<'py>(
+    py: Python<'py>,
+    dictionary: &PyDictionary,
+    guess: &str,
+    feedback: &str,
+    candidates: Option<PyReadonlyArray1<i64>>,
+) -> PyResult<&'py PyArray1<i64>> {
+    let dictionary = &dictionary.inner;
+    let candidates = indices(dictionary, candidates)?;
+    let feedback: Feedback = feedback.parse().map_err(value_error)?;
+    let guess = dictionary
+        .index_of(guess)
+        .ok_or_else(|| value_error(format!("`{}` is not in the dictionary", guess)))?;
+
+    let bytes = dictionary.bytes();
+    let left: Vec<i64> = candidates
+        .into_iter()
+        .filter(|&c| Feedback::score(&bytes[guess], &bytes[c]) == feedback)
+        .map(|c| c as i64)
+        .collect();
+
+    Ok(left.into_pyarray(py))
+}
+
+/**
+    Guesses, candidates and their weights, defaulting to all of the words
+    with uniform weights
+**/
+fn histogram_args(
+    dictionary: &Dictionary,
+    guesses: Option<PyReadonlyArray1<i64>>,
+    candidates: Option<PyReadonlyArray1<i64>>,
+    weights: Option<PyReadonlyArray1<f32>>,
+) -> PyResult<(Vec<usize>, Vec<usize>, Vec<f32>)> {
+    let guesses = indices(dictionary, guesses)?;
+    let candidates = indices(dictionary, candidates)?;
+    let weights = match weights {
+        Some(weights) => weights.as_array().to_vec(),
+        None => vec![1.0; candidates.len()],
+    };
+
+    if weights.len() != candidates.len() {
+        return Err(value_error(format!(
+            "{} weights for {} candidates",
+            weights.len(),
+            candidates.len()
+        )));
+    }
+
+    Ok((guesses, candidates, weights))
+}
+
+/**
+    Row major `(guesses, 243)` histograms computed by the backend
+**/
+fn histogram_bins(
+    dictionary: &Dictionary,
+    backend: &dyn EntropyBackend,
+    guesses: &[usize],
+    candidates: &[usize],
+    weights: &[f32],
+) -> Vec<f32> {
+    let words = dictionary.words();
+    let guesses: Vec<_> = guesses.iter().map(|&g| words[g]).collect();
+    let candidates: Vec<_> = candidates.iter().map(|&c| words[c]).collect();
+
+    backend
+        .histograms(&guesses, &candidates, weights)
+        .iter()
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/**
+    Weighted feedback histogram of every guess over the candidates,
+    `(guesses, 243)` array, `np.bincount` of the `score_matrix` rows
+**/
+#[pyfunction(
+    guesses = "None",
+    candidates = "None",
+    weights = "None",
+    backend = "\"auto\""
+)]
+fn histograms<'py>(
+    py: Python<'py>,
+    dictionary: &PyDictionary,
+    guesses: Option<PyReadonlyArray1<i64>>,
+    candidates: Option<PyReadonlyArray1<i64>>,
+    weights: Option<PyReadonlyArray1<f32>>,
+    backend: &str,
+) -> PyResult<&'py PyArray2<f32>> {
+    let dictionary = &dictionary.inner;
+    let (guesses, candidates, weights) = histogram_args(dictionary, guesses, candidates, weights)?;
+    let backend = self::backend(backend)?;
+
+    let bins =
+        py.allow_threads(|| histogram_bins(dictionary, &*backend, &guesses, &candidates, &weights));
+
+    bins.into_pyarray(py)
+        .reshape([guesses.len(), PATTERN_COUNT])
+}
+
+/**
+    Expected information (bits) of every guess over the weighted candidates
+**/
+#[pyfunction(
+    guesses = "None",
+    candidates = "None",
+    weights = "None",
+    backend = "\"auto\""
+)]
+fn entropies<'py>(
+    py: Python<'py>,
+    dictionary: &PyDictionary,
+    guesses: Option<PyReadonlyArray1<i64>>,
+    candidates: Option<PyReadonlyArray1<i64>>,
+    weights: Option<PyReadonlyArray1<f32>>,
+    backend: &str,
+) -> PyResult<&'py PyArray1<f32>> {
+    let dictionary = &dictionary.inner;
+    let (guesses, candidates, weights) = histogram_args(dictionary, guesses, candidates, weights)?;
+    let backend = self::backend(backend)?;
+
+    let entropies = py.allow_threads(|| {
+        let words = dictionary.words();
+        let guesses: Vec<_> = guesses.iter().map(|&g| words[g]).collect();
+        let candidates: Vec<_> = candidates.iter().map(|&c| words[c]).collect();
+
+        backend.entropies(&guesses, &candidates, &weights)
+    });
+
+    Ok(entropies.into_pyarray(py))
+}
+
+/**
+    Plays one game per answer (all of the words by default) with the
+    strategy, number of guesses it took or -1 when it didn't solve it in
+    `max_guesses`
+**/
+#[pyfunction(
+    answers = "None",
+    strategy = "\"entropy\"",
+    backend = "\"auto\"",
+    max_guesses = "6"
+)]
+fn simulate<'py>(
+    py: Python<'py>,
+    dictionary: &PyDictionary,
+    answers: Option<PyReadonlyArray1<i64>>,
+    strategy: &str,
+    backend: &str,
+    max_guesses: usize,
+) -> PyResult<&'py PyArray1<i64>> {
+    let answers = indices(&dictionary.inner, answers)?;
+    let solver = Solver::new(dictionary.inner.clone(), self::backend(backend)?)
+        .with_strategy(self::strategy(strategy)?.build());
+
+    let turns: Vec<i64> = py.allow_threads(|| {
+        simulate_games(&solver, &answers, max_guesses)
+            .iter()
+            .map(|game| {
+                if game.is_solved() {
+                    game.turns() as i64
+                } else {
+                    -1
+                }
+            })
+            .collect()
+    });
+
+    Ok(turns.into_pyarray(py))
+}
+
+#[pymodule]
+fn wordle(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyDictionary>()?;
+    m.add_function(wrap_pyfunction!(score, m)?)?;
+    m.add_function(wrap_pyfunction!(score_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(filter, m)?)?;
+    m.add_function(wrap_pyfunction!(histograms, m)?)?;
+    m.add_function(wrap_pyfunction!(entropies, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{histogram_bins, score_indices};
+    use wordle_solver::pattern::PATTERN_COUNT;
+    use wordle_solver::{BackendKind, Dictionary};
+
+    // `np.bincount(score_matrix(d)[g], minlength=243) == histograms(d)[g]`
+    #[test]
+    fn histograms_agree_with_score_matrix() {
+        let dictionary = Dictionary::bundled();
+        let answers: Vec<usize> = (0..dictionary.len()).collect();
+        let weights = vec![1.0; answers.len()];
+        let guesses: Vec<usize> = ["sissy", "geese", "llama", "tares"]
+            .iter()
+            .map(|w| dictionary.index_of(w).unwrap())
+            .collect();
+
+        let matrix = score_indices(&dictionary, &guesses, &answers);
+        let mut counts = vec![0.0; guesses.len() * PATTERN_COUNT];
+        for (g, row) in matrix.chunks(answers.len()).enumerate() {
+            for &index in row {
+                counts[g * PATTERN_COUNT + index as usize] += 1.0;
+            }
+        }
+
+        for kind in [BackendKind::Cpu, BackendKind::Simd, BackendKind::Transposed] {
+            let backend = kind.build().unwrap();
+            let bins = histogram_bins(&dictionary, &*backend, &guesses, &answers, &weights);
+
+            assert_eq!(bins, counts);
+        }
+    }
+}
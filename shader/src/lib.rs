@@ -9,7 +9,6 @@
 
 extern crate spirv_std;
 
-use crate::spirv_std::num_traits::Float;
 use glam::UVec3;
 use spirv_std::glam;
 #[cfg(not(target_arch = "spirv"))]
@@ -21,18 +20,6 @@ pub struct AsciiBitSet {
     pub set: u32,
 }
 
-impl AsciiBitSet {
-    #[inline]
-    pub fn is_superset(&self, set: &Self) -> bool {
-        self.set & set.set ^ set.set == 0
-    }
-
-    #[inline]
-    pub fn is_disjoint(&self, set: &Self) -> bool {
-        self.set & set.set == 0
-    }
-}
-
 #[derive(Default, Copy, Clone)]
 #[repr(C)]
 pub struct Fivegram {
@@ -40,25 +27,6 @@ pub struct Fivegram {
     pub letter_mask: u32,
 }
 
-impl Fivegram {
-    #[inline]
-    pub fn exact_match(&self, pattern: &Self) -> bool {
-        self.word & pattern.letter_mask ^ pattern.word == 0
-    }
-
-    #[inline]
-    pub fn any_pos_match(&self, pattern: &Self) -> bool {
-        let intersection =
-            ((self.word & pattern.letter_mask) ^ pattern.word) | !pattern.letter_mask;
-
-        intersection & 0b11111 == 0
-            || intersection >> 5 & 0b11111 == 0
-            || intersection >> 10 & 0b11111 == 0
-            || intersection >> 15 & 0b11111 == 0
-            || intersection >> 20 & 0b11111 == 0
-    }
-}
-
 #[derive(Default, Copy, Clone)]
 #[repr(C)]
 pub struct Word {
@@ -68,54 +36,73 @@ pub struct Word {
 
 pub const FIVEGRAM: usize = 5;
 pub const PATTERN_COUNT: usize = 3usize.pow(FIVEGRAM as u32);
-pub type Patterns = [Pattern; PATTERN_COUNT];
 
-#[derive(Default, Copy, Clone)]
-#[repr(C)]
-pub struct Pattern {
-    pub match_word: Fivegram,
-    pub present_letter: AsciiBitSet,
-    pub absent_word: Fivegram,
-    pub absent_letter: AsciiBitSet,
+#[inline]
+fn letter(word: u32, i: usize) -> u32 {
+    word >> (i * 5) as u32 & 0b11111
 }
 
-impl Pattern {
-    #[inline]
-    pub fn matches_word(&self, word: &Word) -> bool {
-        word.letters.is_superset(&self.present_letter)
-            && word.letters.is_disjoint(&self.absent_letter)
-            && word.fivegram.exact_match(&self.match_word)
-            && !word.fivegram.any_pos_match(&self.absent_word)
+/**
+    Base 3 feedback index of the guess to the answer, same as `Feedback::score`
+    on the host: greens first, then the yellows left to right out of the
+    answer letters not yet taken
+**/
+#[inline]
+pub fn score(guess: u32, answer: u32) -> usize {
+    let mut taken = 0u32;
+    for i in 0..FIVEGRAM {
+        if letter(guess, i) == letter(answer, i) {
+            taken |= 1 << i;
+        }
+    }
+
+    let mut index = 0;
+    let mut power = 1;
+    for i in 0..FIVEGRAM {
+        let mut tile = 2;
+        if taken >> i & 1 == 1 && letter(guess, i) == letter(answer, i) {
+            tile = 0;
+        } else {
+            for j in 0..FIVEGRAM {
+                if tile == 2 && taken >> j & 1 == 0 && letter(guess, i) == letter(answer, j) {
+                    taken |= 1 << j;
+                    tile = 1;
+                }
+            }
+        }
+        index += tile * power;
+        power *= 3;
     }
-}
 
-const WORD_COUNT: usize = 12972;
+    index
+}
 
 // LocalSize/numthreads of (x = 64, y = 1, z = 1)
 #[spirv(compute(threads(64)))]
 pub fn main(
     #[spirv(global_invocation_id)] id: UVec3,
-    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] entropies: &mut [f32],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] histograms: &mut [f32],
     #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] words: &[Word],
-    #[spirv(storage_buffer, descriptor_set = 0, binding = 2)] patterns: &[Patterns],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 2)] guesses: &[Word],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 3)] weights: &[f32],
+    // [guess count, candidate count]
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 4)] counts: &[u32],
 ) {
     let idx = id.x as usize;
-
-    let mut entropy = 0f32;
-    for i in 0..PATTERN_COUNT {
-        let mut matches = 0f32;
-
-        for j in 0..WORD_COUNT {
-            if patterns[idx][i].matches_word(&words[j]) {
-                matches += 1.0;
-            }
+    let guess_count = counts[0] as usize;
+    let candidate_count = counts[1] as usize;
+
+    // last workgroup might be only partially filled
+    if idx < guess_count {
+        let offset = idx * PATTERN_COUNT;
+        for i in 0..PATTERN_COUNT {
+            histograms[offset + i] = 0.0;
         }
 
-        if matches != 0.0 {
-            let p = matches / WORD_COUNT as f32;
-            entropy += -p * p.log2();
+        let guess = guesses[idx].fivegram.word;
+        for j in 0..candidate_count {
+            let bin = offset + score(guess, words[j].fivegram.word);
+            histograms[bin] += weights[j];
         }
     }
-
-    entropies[idx] = entropy;
 }
@@ -0,0 +1,200 @@
+use crate::backend::histogram_to_entropy;
+use crate::strategy::{top_k, Entropy};
+use crate::{Feedback, Solver};
+
+/**
+    One guess of a played game next to the best one available at that point.
+
+    `expected_bits` is the entropy of the feedback over the candidates left,
+    `actual_bits` is what the feedback turned out to be worth:
+    `log2(candidates / remaining)`.
+**/
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessAnalysis {
+    pub guess: usize,
+    pub feedback: Feedback,
+    pub candidates: usize,
+    pub remaining: usize,
+    pub expected_bits: f32,
+    pub actual_bits: f32,
+    pub best: usize,
+    pub best_bits: f32,
+    /**
+        0 to 99, how close the expected information is to the best guess
+    **/
+    pub skill: u32,
+    /**
+        0 to 99, share of the candidates which would have left more words
+        than the actual feedback did (the equal ones count half)
+    **/
+    pub luck: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameAnalysis {
+    pub guesses: Vec<GuessAnalysis>,
+    /**
+        Averages over the guesses made while the answer was still unknown
+    **/
+    pub skill: u32,
+    pub luck: u32,
+}
+
+fn grade(fraction: f32) -> u32 {
+    (fraction * 99.0).round().clamp(0.0, 99.0) as u32
+}
+
+fn analyze_guess(solver: &Solver, guess: usize, answer: usize) -> GuessAnalysis {
+    let dictionary = solver.dictionary();
+    let bytes = dictionary.bytes();
+    let candidates = solver.candidates().len();
+    let feedback = Feedback::score(&bytes[guess], &bytes[answer]);
+
+    let total = candidates as f32;
+    let entropies: Vec<f32> = solver
+        .histograms()
+        .iter()
+        .map(|bins| histogram_to_entropy(bins, total))
+        .collect();
+    let best = match solver.candidates() {
+        [only] => *only,
+        _ => top_k(&Entropy, &entropies, bytes, 1)[0],
+    };
+
+    let buckets = solver.explain(guess);
+    let expected_bits = entropies[guess];
+    let best_bits = entropies[best];
+
+    let remaining = buckets
+        .iter()
+        .find(|(f, _)| *f == feedback)
+        .map_or(0, |(_, words)| words.len());
+    let larger: usize = buckets
+        .iter()
+        .map(|(_, words)| words.len())
+        .filter(|&n| n > remaining)
+        .sum();
+
+    let skill = if best_bits > 0.0 {
+        grade(expected_bits / best_bits)
+    } else {
+        // one candidate left, only guessing it counts
+        grade((guess == best) as u32 as f32)
+    };
+
+    GuessAnalysis {
+        guess,
+        feedback,
+        candidates,
+        remaining,
+        expected_bits,
+        actual_bits: (candidates as f32 / remaining.max(1) as f32).log2(),
+        best,
+        best_bits,
+        skill,
+        luck: grade((larger as f32 + remaining as f32 / 2.0) / candidates.max(1) as f32),
+    }
+}
+
+/**
+    Replays the guesses against the answer from the solver state, grading
+    each of them by the information it was expected to give and the one
+    it actually gave
+**/
+pub fn analyze(solver: &Solver, answer: usize, guesses: &[usize]) -> GameAnalysis {
+    let mut solver = solver.clone();
+    let mut analysis = vec![];
+
+    for &guess in guesses {
+        let guess = analyze_guess(&solver, guess, answer);
+        solver.apply_index(guess.guess, guess.feedback);
+        let solved = guess.feedback.is_solved();
+        analysis.push(guess);
+        if solved {
+            break;
+        }
+    }
+
+    let graded: Vec<&GuessAnalysis> = analysis.iter().filter(|g| g.candidates > 1).collect();
+    let average = |grade: fn(&GuessAnalysis) -> u32| match graded.len() {
+        0 => 99,
+        n => (graded.iter().map(|g| grade(g)).sum::<u32>() as f32 / n as f32).round() as u32,
+    };
+
+    GameAnalysis {
+        skill: average(|g| g.skill),
+        luck: average(|g| g.luck),
+        guesses: analysis,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::analysis::analyze;
+    use crate::backend::CpuBackend;
+    use crate::{Dictionary, Solver};
+
+    #[test]
+    fn skill_and_luck() {
+        let dictionary =
+            Arc::new(Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap());
+        let solver = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let idx = |w: &str| dictionary.index_of(w).unwrap();
+
+        // humph only splits off itself and rebut, tares tells all of them apart
+        let game = analyze(
+            &solver,
+            idx("sorel"),
+            &[idx("humph"), idx("tares"), idx("sorel")],
+        );
+        let words: Vec<String> = game
+            .guesses
+            .iter()
+            .map(|g| dictionary.word(g.guess))
+            .collect();
+        assert_eq!(words, vec!["humph", "tares", "sorel"]);
+
+        let humph = &game.guesses[0];
+        assert_eq!((humph.candidates, humph.remaining), (7, 5));
+        assert!(humph.expected_bits < humph.best_bits);
+        assert!((humph.actual_bits - (7.0f32 / 5.0).log2()).abs() < 1e-6);
+        assert!(humph.skill < 50);
+        // the largest bucket, half of it counts
+        assert_eq!(humph.luck, 35);
+
+        let tares = &game.guesses[1];
+        assert_eq!((tares.candidates, tares.remaining), (5, 1));
+        assert_eq!(tares.skill, 99);
+
+        let sorel = &game.guesses[2];
+        assert_eq!((sorel.candidates, sorel.remaining), (1, 1));
+        assert_eq!(sorel.skill, 99);
+        assert_eq!(sorel.actual_bits, 0.0);
+
+        // the game grades skip the last guess, the answer was known by then
+        assert_eq!(game.skill, (humph.skill + 99 + 1) / 2);
+
+        // guesses after the solve are ignored
+        let game = analyze(&solver, idx("tares"), &[idx("tares"), idx("sorel")]);
+        assert_eq!(game.guesses.len(), 1);
+    }
+
+    // the best guess is picked by the same bits the skill is graded by
+    #[test]
+    fn repeated_letters_best() {
+        let dictionary = Arc::new(Dictionary::bundled());
+        let solver = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let idx = |w: &str| dictionary.index_of(w).unwrap();
+
+        let game = analyze(&solver, idx("geese"), &[idx("sissy"), idx("geese")]);
+        for guess in &game.guesses {
+            assert!(guess.expected_bits <= guess.best_bits);
+        }
+        let sissy = &game.guesses[0];
+        assert!((sissy.expected_bits - 3.70).abs() < 0.01);
+        assert!(sissy.skill < 99);
+    }
+}
@@ -0,0 +1,367 @@
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{histogram_to_entropy, EntropyBackend};
+use crate::fivegram::FIVEGRAM;
+use crate::report::GuessReport;
+use crate::solver::SolverError;
+use crate::word::{wordbytes_from_str, WordBytes};
+use crate::{Dictionary, Feedback, Solver, StrategyKind};
+
+const DEFAULT_TOP: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    pub guess: String,
+    pub feedback: Feedback,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SuggestRequest {
+    #[serde(default)]
+    pub history: Vec<Turn>,
+    #[serde(default = "default_strategy")]
+    pub strategy: StrategyKind,
+    #[serde(default = "default_top")]
+    pub top: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestResponse {
+    pub candidates: usize,
+    pub suggestions: Vec<GuessReport>,
+}
+
+impl SuggestResponse {
+    pub fn new(solver: &Solver, top: usize) -> Self {
+        SuggestResponse {
+            candidates: solver.candidates().len(),
+            suggestions: solver.report(top),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bucket {
+    pub feedback: Feedback,
+    pub emoji: String,
+    pub count: usize,
+    pub probability: f32,
+    /**
+        The bucket's term of the entropy, `-p log2 p`
+    **/
+    pub bits: f32,
+    pub words: Vec<String>,
+}
+
+/**
+    How the guess splits the remaining candidates
+**/
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainResponse {
+    pub guess: String,
+    pub entropy: f32,
+    pub buckets: Vec<Bucket>,
+}
+
+impl ExplainResponse {
+    pub fn new(solver: &Solver, guess: &str) -> Result<Self, ApiError> {
+        let idx = solver
+            .dictionary()
+            .index_of(guess)
+            .ok_or_else(|| SolverError::UnknownWord(guess.to_string()))?;
+
+        // same histogram the rankings are built from, weighted when noisy
+        let bins = solver.histogram(idx);
+        let total = solver.total_weight();
+
+        Ok(ExplainResponse {
+            guess: guess.to_string(),
+            entropy: histogram_to_entropy(&bins, total),
+            buckets: solver
+                .explain(idx)
+                .into_iter()
+                .map(|(feedback, words)| {
+                    let probability = bins[feedback.index()] / total;
+                    Bucket {
+                        feedback,
+                        emoji: feedback.to_emoji(),
+                        count: words.len(),
+                        probability,
+                        bits: if probability > 0.0 {
+                            -probability * probability.log2()
+                        } else {
+                            0.0
+                        },
+                        words: words.iter().map(|&w| solver.dictionary().word(w)).collect(),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    /**
+        Table of the buckets, largest first, with up to `samples` of the
+        words in each
+    **/
+    pub fn write_text(&self, out: &mut impl Write, samples: usize) -> std::io::Result<()> {
+        let candidates: usize = self.buckets.iter().map(|b| b.count).sum();
+        writeln!(
+            out,
+            "{}: {:.2} bits, {} buckets over {} candidates",
+            self.guess,
+            self.entropy,
+            self.buckets.len(),
+            candidates
+        )?;
+
+        for bucket in &self.buckets {
+            let share = if self.entropy > 0.0 {
+                bucket.bits / self.entropy * 100.0
+            } else {
+                0.0
+            };
+            let mut words = bucket.words[..bucket.words.len().min(samples)].join(" ");
+            if bucket.words.len() > samples {
+                words.push_str(" ...");
+            }
+
+            writeln!(
+                out,
+                "{} {:>5} {:>6.2}% {:>5.2} bits {:>5.1}%  {}",
+                bucket.emoji,
+                bucket.count,
+                bucket.probability * 100.0,
+                bucket.bits,
+                share,
+                words
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScoreRequest {
+    pub guess: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreResponse {
+    pub feedback: Feedback,
+    pub emoji: String,
+    pub solved: bool,
+}
+
+impl ScoreResponse {
+    pub fn new(guess: &str, answer: &str) -> Result<Self, ApiError> {
+        let feedback = Feedback::score(&parse_word(guess)?, &parse_word(answer)?);
+
+        Ok(ScoreResponse {
+            feedback,
+            emoji: feedback.to_emoji(),
+            solved: feedback.is_solved(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    InvalidWord(String),
+    Solver(SolverError),
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::InvalidWord(word) => {
+                write!(f, "`{}` is not a {}-letter lower-case word", word, FIVEGRAM)
+            }
+            ApiError::Solver(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<SolverError> for ApiError {
+    fn from(err: SolverError) -> Self {
+        ApiError::Solver(err)
+    }
+}
+
+fn default_strategy() -> StrategyKind {
+    StrategyKind::Entropy
+}
+
+fn default_top() -> usize {
+    DEFAULT_TOP
+}
+
+fn parse_word(word: &str) -> Result<WordBytes, ApiError> {
+    if word.len() == FIVEGRAM && word.bytes().all(|b| b.is_ascii_lowercase()) {
+        Ok(wordbytes_from_str(word))
+    } else {
+        Err(ApiError::InvalidWord(word.to_string()))
+    }
+}
+
+/**
+    Request handlers shared by the front-ends (HTTP, JSON-RPC), dictionary
+    and backend are built once and shared between the requests
+**/
+#[derive(Clone)]
+pub struct Api {
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+}
+
+impl Api {
+    pub fn new(dictionary: Arc<Dictionary>, backend: Arc<dyn EntropyBackend>) -> Self {
+        Api {
+            dictionary,
+            backend,
+        }
+    }
+
+    pub fn dictionary(&self) -> &Arc<Dictionary> {
+        &self.dictionary
+    }
+
+    pub fn backend(&self) -> &Arc<dyn EntropyBackend> {
+        &self.backend
+    }
+
+    pub fn solver(&self, strategy: StrategyKind) -> Solver {
+        Solver::new(self.dictionary.clone(), self.backend.clone()).with_strategy(strategy.build())
+    }
+
+    pub fn suggest(&self, request: &SuggestRequest) -> Result<SuggestResponse, ApiError> {
+        let mut solver = self.solver(request.strategy);
+        for turn in &request.history {
+            solver.apply(&turn.guess, turn.feedback)?;
+        }
+
+        Ok(SuggestResponse::new(&solver, request.top))
+    }
+
+    pub fn score(&self, request: &ScoreRequest) -> Result<ScoreResponse, ApiError> {
+        ScoreResponse::new(&request.guess, &request.answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::api::{Api, ApiError, ExplainResponse, ScoreRequest, SuggestRequest};
+    use crate::backend::CpuBackend;
+    use crate::solver::SolverError;
+    use crate::{Dictionary, Solver};
+
+    fn api() -> Api {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Api::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    #[test]
+    fn suggest() {
+        let request: SuggestRequest = serde_json::from_str(
+            r#"{"history": [{"guess": "cigar", "feedback": "BBBBB"}], "top": 2}"#,
+        )
+        .unwrap();
+
+        let response = api().suggest(&request).unwrap();
+        assert_eq!(response.candidates, 1);
+        assert_eq!(response.suggestions.len(), 2);
+    }
+
+    #[test]
+    fn explain_text() {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+        let solver = Solver::new(Arc::new(dictionary), Arc::new(CpuBackend));
+        let explanation = ExplainResponse::new(&solver, "cigar").unwrap();
+
+        let bits: f32 = explanation.buckets.iter().map(|b| b.bits).sum();
+        assert!((bits - explanation.entropy).abs() < 1e-5);
+
+        let mut out = vec![];
+        explanation.write_text(&mut out, 1).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "cigar: 2.52 bits, 6 buckets over 7 candidates");
+        assert_eq!(
+            lines[1],
+            "⬛⬛⬛⬛🟨     2  28.57%  0.52 bits  20.5%  rebut ..."
+        );
+    }
+
+    #[test]
+    fn explain_matches_report() {
+        let mut solver = Solver::new(Arc::new(Dictionary::bundled()), Arc::new(CpuBackend));
+        let words = ["sissy", "geese", "llama", "tares"];
+        for word in words {
+            let guess = solver.dictionary().index_of(word).unwrap();
+            let explanation = ExplainResponse::new(&solver, word).unwrap();
+            assert_eq!(explanation.entropy, solver.guess_report(guess).entropy);
+        }
+
+        let mut noisy = solver.clone().with_noise(0.05);
+        noisy.apply("tares", "BBBBY".parse().unwrap()).unwrap();
+        solver.apply("tares", "BBBBY".parse().unwrap()).unwrap();
+        for solver in [solver, noisy] {
+            for word in words {
+                let guess = solver.dictionary().index_of(word).unwrap();
+                let explanation = ExplainResponse::new(&solver, word).unwrap();
+                let bits: f32 = explanation.buckets.iter().map(|b| b.bits).sum();
+
+                assert_eq!(explanation.entropy, solver.guess_report(guess).entropy);
+                assert!((bits - explanation.entropy).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn suggest_unknown_word() {
+        let request: SuggestRequest =
+            serde_json::from_str(r#"{"history": [{"guess": "zzzzz", "feedback": "BBBBB"}]}"#)
+                .unwrap();
+
+        assert_eq!(
+            api().suggest(&request).unwrap_err(),
+            ApiError::Solver(SolverError::UnknownWord("zzzzz".to_string()))
+        );
+    }
+
+    #[test]
+    fn score() {
+        let response = api()
+            .score(&ScoreRequest {
+                guess: "tares".to_string(),
+                answer: "sorel".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({"feedback": "BBGGY", "emoji": "⬛⬛🟩🟩🟨", "solved": false})
+        );
+        assert!(api()
+            .score(&ScoreRequest {
+                guess: "TARES".to_string(),
+                answer: "sorel".to_string(),
+            })
+            .is_err());
+    }
+}
@@ -1,4 +1,3 @@
-use crate::simd_pattern::Simd;
 use std::fmt::{Display, Formatter};
 
 /**
@@ -47,20 +46,10 @@ impl AsciiBitSet {
         self.set & set.set ^ set.set == 0
     }
 
-    #[inline]
-    pub fn is_superset_simd(a: &Simd, b: &Simd) -> Simd {
-        a & b ^ b
-    }
-
     #[inline]
     pub fn is_disjoint(&self, set: &Self) -> bool {
         self.set & set.set == 0
     }
-
-    #[inline]
-    pub fn is_disjoint_simd(a: &Simd, b: &Simd) -> Simd {
-        a & b
-    }
 }
 
 #[cfg(test)]
@@ -71,30 +60,8 @@ mod tests {
 
     use proptest::prelude::*;
 
-    use crate::simd_pattern::Simd;
-    use crate::simd_pattern::SIMD_WIDTH;
-
     use super::AsciiBitSet;
 
-    #[test]
-    fn proptest_is_superset_simd() {
-        proptest!(|(left in ["[a-z]{0,16}"; SIMD_WIDTH], right in ["[a-z]{0,16}"; SIMD_WIDTH])| {
-            let expected: [bool; SIMD_WIDTH] = left.iter().zip(right.iter()).map(|(left, right)| {
-                let lhs: HashSet<char> = HashSet::from_iter(left.chars());
-                let rhs: HashSet<char> = HashSet::from_iter(right.chars());
-
-                lhs.is_superset(&rhs)
-            }).collect::<Vec<bool>>().try_into().unwrap();
-
-            let left = Simd::from_array(left.map(|l| AsciiBitSet::from_bytes(l.as_bytes()).set));
-            let right = Simd::from_array(right.map(|r| AsciiBitSet::from_bytes(r.as_bytes()).set));
-
-            let res = AsciiBitSet::is_superset_simd(&left, &right);
-            let zero = Simd::splat(0);
-            assert_eq!(res.lanes_eq(zero).to_array(), expected);
-        });
-    }
-
     #[test]
     fn proptest_is_superset() {
         proptest!(|(left in "[a-z]{0,16}", right in "[a-z]{0,16}")| {
@@ -108,25 +75,6 @@ mod tests {
         });
     }
 
-    #[test]
-    fn proptest_is_disjoint_simd() {
-        proptest!(|(left in ["[a-z]{0,16}"; SIMD_WIDTH], right in ["[a-z]{0,16}"; SIMD_WIDTH])| {
-            let expected: [bool; SIMD_WIDTH] = left.iter().zip(right.iter()).map(|(left, right)| {
-                let lhs: HashSet<char> = HashSet::from_iter(left.chars());
-                let rhs: HashSet<char> = HashSet::from_iter(right.chars());
-
-                lhs.is_disjoint(&rhs)
-            }).collect::<Vec<bool>>().try_into().unwrap();
-
-            let left = Simd::from_array(left.map(|l| AsciiBitSet::from_bytes(l.as_bytes()).set));
-            let right = Simd::from_array(right.map(|r| AsciiBitSet::from_bytes(r.as_bytes()).set));
-
-            let res = AsciiBitSet::is_disjoint_simd(&left, &right);
-            let zero = Simd::splat(0);
-            assert_eq!(res.lanes_eq(zero).to_array(), expected);
-        });
-    }
-
     #[test]
     fn proptest_is_disjoint() {
         proptest!(|(left in "[a-z]{0,16}", right in "[a-z]{0,16}")| {
@@ -0,0 +1,228 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::pattern::PATTERN_COUNT;
+#[cfg(feature = "gpu")]
+use crate::shader::GpuBackend;
+use crate::word::WordBytes;
+use crate::{Feedback, Word};
+
+#[cfg(feature = "simd")]
+pub use simd::{SimdBackend, SimdWidth};
+#[cfg(feature = "simd")]
+pub use transposed::TransposedBackend;
+
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "simd")]
+mod transposed;
+
+/**
+    Weighted number of candidates giving each feedback to the guess, indexed
+    by `Feedback::index`
+**/
+pub type Histogram = [f32; PATTERN_COUNT];
+
+/**
+    Common interface of the CPU, SIMD and GPU implementations.
+
+    Every backend builds the feedback histogram of each guess over the
+    weighted candidates with the `Feedback::score` rules, the same ones the
+    candidates are filtered by. Entropies are derived from those histograms.
+**/
+pub trait EntropyBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn histograms(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<Histogram>;
+
+    fn entropies(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<f32> {
+        let total = weights.iter().sum();
+
+        self.histograms(guesses, candidates, weights)
+            .iter()
+            .map(|bins| histogram_to_entropy(bins, total))
+            .collect()
+    }
+}
+
+/**
+    Backends by name, the ones which are not compiled in (see `simd` and `gpu`
+    features) or can't run on this machine fail to build
+**/
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BackendKind {
+    Auto,
+    Cpu,
+    Simd,
+    Transposed,
+    Gpu,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    NotCompiled(&'static str),
+    NoAdapter,
+}
+
+impl Display for BackendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackendError::NotCompiled(feature) => {
+                write!(f, "backend requires the `{}` feature", feature)
+            }
+            BackendError::NoAdapter => write!(f, "failed to find an appropriate GPU adapter"),
+        }
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+impl BackendKind {
+    pub fn build(self) -> Result<Arc<dyn EntropyBackend>, BackendError> {
+        match self {
+            BackendKind::Auto => Ok(auto()),
+            BackendKind::Cpu => Ok(Arc::new(CpuBackend)),
+            #[cfg(feature = "simd")]
+            BackendKind::Simd => Ok(Arc::new(SimdBackend::detect())),
+            #[cfg(feature = "simd")]
+            BackendKind::Transposed => Ok(Arc::new(TransposedBackend::detect())),
+            #[cfg(not(feature = "simd"))]
+            BackendKind::Simd | BackendKind::Transposed => Err(BackendError::NotCompiled("simd")),
+            #[cfg(feature = "gpu")]
+            BackendKind::Gpu => GpuBackend::new()
+                .map(|gpu| Arc::new(gpu) as Arc<dyn EntropyBackend>)
+                .ok_or(BackendError::NoAdapter),
+            #[cfg(not(feature = "gpu"))]
+            BackendKind::Gpu => Err(BackendError::NotCompiled("gpu")),
+        }
+    }
+}
+
+/**
+    GPU if there is an adapter to run it on, widest supported SIMD otherwise,
+    plain CPU when neither is compiled in
+**/
+pub fn auto() -> Arc<dyn EntropyBackend> {
+    #[cfg(feature = "gpu")]
+    if let Some(gpu) = GpuBackend::new() {
+        return Arc::new(gpu);
+    }
+
+    #[cfg(feature = "simd")]
+    return Arc::new(SimdBackend::detect());
+
+    #[cfg(not(feature = "simd"))]
+    return Arc::new(CpuBackend);
+}
+
+/**
+    Histogram of every guess, spread over the rayon pool with the `parallel`
+    feature, sequentially without it (WASM has no threads)
+**/
+pub(crate) fn map_guesses<F>(guesses: &[Word], f: F) -> Vec<Histogram>
+where
+    F: Fn(&Word) -> Histogram + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    return guesses.par_iter().map(f).collect();
+
+    #[cfg(not(feature = "parallel"))]
+    return guesses.iter().map(f).collect();
+}
+
+/**
+    Same as `map_guesses` for the kernels scoring `chunk` guesses at once,
+    the last chunk may be shorter
+**/
+#[cfg(feature = "simd")]
+pub(crate) fn map_guess_chunks<F>(guesses: &[Word], chunk: usize, f: F) -> Vec<Histogram>
+where
+    F: Fn(&[Word]) -> Vec<Histogram> + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    return guesses.par_chunks(chunk).flat_map_iter(f).collect();
+
+    #[cfg(not(feature = "parallel"))]
+    return guesses.chunks(chunk).flat_map(f).collect();
+}
+
+pub fn histogram_to_entropy(bins: &Histogram, total: f32) -> f32 {
+    bins.iter()
+        .filter(|&&f| f != 0.0)
+        .map(|&f| f / total)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+pub struct CpuBackend;
+
+impl EntropyBackend for CpuBackend {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn histograms(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<Histogram> {
+        let candidates: Vec<WordBytes> = candidates.iter().map(|w| w.to_wordbytes()).collect();
+
+        map_guesses(guesses, |guess| {
+            score_candidates(&guess.to_wordbytes(), &candidates, weights)
+        })
+    }
+}
+
+fn score_candidates(guess: &WordBytes, candidates: &[WordBytes], weights: &[f32]) -> Histogram {
+    let mut bins = [0.0; PATTERN_COUNT];
+
+    for (answer, weight) in candidates.iter().zip(weights) {
+        bins[Feedback::score(guess, answer).index()] += weight;
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::score_candidates;
+    use crate::Dictionary;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    pub(super) const KNOWN_WORD: &str = "sorel";
+    pub(super) const KNOWN_BINS: [usize; 182] = [
+        986, 923, 366, 324, 193, 129, 375, 349, 54, 374, 256, 70, 200, 70, 22, 74, 54, 8, 189, 170,
+        57, 39, 17, 14, 77, 65, 13, 636, 403, 203, 131, 44, 28, 97, 41, 11, 388, 148, 41, 83, 18,
+        9, 16, 6, 1, 122, 66, 19, 12, 9, 3, 18, 7, 368, 369, 107, 19, 11, 5, 143, 96, 6, 311, 76,
+        36, 20, 3, 1, 69, 16, 3, 73, 43, 22, 4, 2, 11, 11, 5, 434, 326, 106, 123, 66, 31, 114, 119,
+        17, 54, 11, 4, 15, 2, 1, 13, 7, 1, 23, 30, 1, 2, 5, 2, 321, 158, 58, 63, 10, 4, 43, 7, 6,
+        39, 9, 5, 3, 11, 4, 1, 125, 94, 15, 6, 4, 1, 26, 24, 3, 57, 6, 4, 5, 1, 5, 2, 1, 5, 4, 1,
+        94, 6, 33, 21, 2, 10, 31, 2, 45, 3, 16, 1, 3, 11, 1, 3, 9, 1, 35, 1, 15, 3, 1, 2, 1, 8, 2,
+        5, 1, 2, 53, 4, 9, 1, 1, 15, 2, 13, 1, 1, 3, 4, 1,
+    ];
+
+    #[test]
+    fn known_bins_cpu() {
+        let dictionary = Dictionary::bundled();
+        let (bytes, words) = (dictionary.bytes(), dictionary.words());
+        let weights = vec![1.0; words.len()];
+
+        let sorel_idx = words
+            .iter()
+            .enumerate()
+            .find(|(_, w)| w.to_str() == KNOWN_WORD)
+            .unwrap()
+            .0;
+        let bins: Vec<usize> = score_candidates(&bytes[sorel_idx], bytes, &weights)
+            .iter()
+            .map(|&x| x as usize)
+            .filter(|&x| x != 0)
+            .collect();
+
+        let bh: HashSet<usize> = HashSet::from_iter(bins);
+        let eh: HashSet<usize> = HashSet::from_iter(KNOWN_BINS);
+
+        assert_eq!(bh, eh)
+    }
+}
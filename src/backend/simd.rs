@@ -0,0 +1,228 @@
+use core_simd::{LaneCount, SupportedLaneCount};
+
+use crate::backend::{map_guess_chunks, EntropyBackend, Histogram};
+use crate::pattern::PATTERN_COUNT;
+use crate::simd_words::Simd;
+use crate::{Feedback, Word};
+
+type Kernel<const LANES: usize> = fn(&[Word], &[f32], &[Word]) -> Vec<Histogram>;
+
+/**
+    Register width to bundle the guesses by, 128-bit is available everywhere
+    (SSE, ARM-neon), wider ones depend on the CPU
+**/
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimdWidth {
+    Bits128,
+    Bits256,
+    Bits512,
+}
+
+impl SimdWidth {
+    pub fn detect() -> Self {
+        [SimdWidth::Bits512, SimdWidth::Bits256]
+            .into_iter()
+            .find(|width| width.is_supported())
+            .unwrap_or(SimdWidth::Bits128)
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn is_supported(&self) -> bool {
+        match self {
+            SimdWidth::Bits128 => true,
+            SimdWidth::Bits256 => is_x86_feature_detected!("avx2"),
+            SimdWidth::Bits512 => is_x86_feature_detected!("avx512f"),
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    pub fn is_supported(&self) -> bool {
+        *self == SimdWidth::Bits128
+    }
+}
+
+pub struct SimdBackend {
+    width: SimdWidth,
+}
+
+impl SimdBackend {
+    pub fn detect() -> Self {
+        SimdBackend {
+            width: SimdWidth::detect(),
+        }
+    }
+}
+
+impl EntropyBackend for SimdBackend {
+    fn name(&self) -> &'static str {
+        match self.width {
+            SimdWidth::Bits128 => "simd-128",
+            SimdWidth::Bits256 => "simd-256",
+            SimdWidth::Bits512 => "simd-512",
+        }
+    }
+
+    fn histograms(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<Histogram> {
+        match self.width {
+            SimdWidth::Bits128 => {
+                score_freq_simd::<4>(guesses, candidates, weights, score_simd::<4>)
+            }
+            SimdWidth::Bits256 => {
+                score_freq_simd::<8>(guesses, candidates, weights, wide::score_256)
+            }
+            SimdWidth::Bits512 => {
+                score_freq_simd::<16>(guesses, candidates, weights, wide::score_512)
+            }
+        }
+    }
+}
+
+fn score_freq_simd<const LANES: usize>(
+    guesses: &[Word],
+    candidates: &[Word],
+    weights: &[f32],
+    kernel: Kernel<LANES>,
+) -> Vec<Histogram>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    map_guess_chunks(guesses, LANES, |chunk| kernel(candidates, weights, chunk))
+}
+
+/**
+    Histograms of up to `LANES` guesses at once, every candidate is splat and
+    scored against all of them
+**/
+#[inline(always)]
+fn score_simd<const LANES: usize>(
+    words: &[Word],
+    weights: &[f32],
+    guesses: &[Word],
+) -> Vec<Histogram>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let mut lanes = [0u32; LANES];
+    for (lane, guess) in lanes.iter_mut().zip(guesses) {
+        *lane = guess.fivegram.word;
+    }
+    let lanes = Simd::from_array(lanes);
+    let mut bins = vec![[0.0; PATTERN_COUNT]; guesses.len()];
+
+    for (other, &weight) in words.iter().zip(weights) {
+        let feedback = Feedback::score_simd(lanes, Simd::splat(other.fivegram.word)).to_array();
+        for (histogram, &index) in bins.iter_mut().zip(&feedback) {
+            histogram[index as usize] += weight;
+        }
+    }
+
+    bins
+}
+
+/**
+    Generic kernel re-compiled with the wider registers enabled, `SimdBackend`
+    is only constructed for the widths CPU supports
+**/
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod wide {
+    use super::score_simd;
+    use crate::backend::Histogram;
+    use crate::Word;
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn score_avx2(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        score_simd::<8>(words, weights, guesses)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn score_avx512(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        score_simd::<16>(words, weights, guesses)
+    }
+
+    pub fn score_256(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        unsafe { score_avx2(words, weights, guesses) }
+    }
+
+    pub fn score_512(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        unsafe { score_avx512(words, weights, guesses) }
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+mod wide {
+    use super::score_simd;
+    use crate::backend::Histogram;
+    use crate::Word;
+
+    pub fn score_256(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        score_simd::<8>(words, weights, guesses)
+    }
+
+    pub fn score_512(words: &[Word], weights: &[f32], guesses: &[Word]) -> Vec<Histogram> {
+        score_simd::<16>(words, weights, guesses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core_simd::{LaneCount, SupportedLaneCount};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    use super::score_simd;
+    use crate::backend::score_candidates;
+    use crate::backend::tests::{KNOWN_BINS, KNOWN_WORD};
+    use crate::Dictionary;
+
+    fn check_known_bins<const LANES: usize>()
+    where
+        LaneCount<LANES>: SupportedLaneCount,
+    {
+        let dictionary = Dictionary::bundled();
+        let words = dictionary.words();
+        let weights = vec![1.0; words.len()];
+
+        let sorel_idx = words
+            .iter()
+            .enumerate()
+            .find(|(_, w)| w.to_str() == KNOWN_WORD)
+            .unwrap()
+            .0;
+
+        let bins: Vec<usize> = score_simd::<LANES>(words, &weights, &words[sorel_idx..=sorel_idx])
+            [0]
+        .iter()
+        .map(|&x| x as usize)
+        .filter(|&x| x != 0)
+        .collect();
+
+        let bh: HashSet<usize> = HashSet::from_iter(bins);
+        let eh: HashSet<usize> = HashSet::from_iter(KNOWN_BINS);
+
+        assert_eq!(bh, eh)
+    }
+
+    #[test]
+    fn known_bins_simd() {
+        check_known_bins::<4>();
+        check_known_bins::<8>();
+        check_known_bins::<16>();
+    }
+
+    #[test]
+    fn weighted_bins_scale() {
+        let dictionary = Dictionary::bundled();
+        let (bytes, words) = (dictionary.bytes(), dictionary.words());
+        let ones = vec![1.0; words.len()];
+        let halves = vec![0.5; words.len()];
+
+        let half = score_simd::<8>(words, &halves, &words[..3]);
+        assert_eq!(half.len(), 3);
+        for (guess, half) in bytes.iter().zip(&half) {
+            let full = score_candidates(guess, bytes, &ones);
+            for (f, h) in full.iter().zip(half.iter()) {
+                assert_eq!(f / 2.0, *h);
+            }
+        }
+    }
+}
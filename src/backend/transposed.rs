@@ -0,0 +1,206 @@
+use core_simd::{LaneCount, SupportedLaneCount};
+
+use crate::backend::{map_guesses, EntropyBackend, Histogram, SimdWidth};
+use crate::pattern::PATTERN_COUNT;
+use crate::simd_words::{Simd, SimdWords};
+use crate::{Feedback, Word};
+
+type Kernel<const LANES: usize> = fn(&[SimdWords<LANES>], u32) -> Histogram;
+
+/**
+    Vectorises over the candidates instead of the guesses: every guess is
+    splat and scored against `LANES` transposed words at a time, so there is
+    no per-chunk guess bundling and no wasted lanes in the last guess chunk.
+**/
+pub struct TransposedBackend {
+    width: SimdWidth,
+}
+
+impl TransposedBackend {
+    pub fn detect() -> Self {
+        TransposedBackend {
+            width: SimdWidth::detect(),
+        }
+    }
+}
+
+impl EntropyBackend for TransposedBackend {
+    fn name(&self) -> &'static str {
+        match self.width {
+            SimdWidth::Bits128 => "transposed-128",
+            SimdWidth::Bits256 => "transposed-256",
+            SimdWidth::Bits512 => "transposed-512",
+        }
+    }
+
+    fn histograms(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<Histogram> {
+        match self.width {
+            SimdWidth::Bits128 => {
+                score_freq_transposed::<4>(guesses, candidates, weights, score_transposed)
+            }
+            SimdWidth::Bits256 => {
+                score_freq_transposed::<8>(guesses, candidates, weights, wide::score_256)
+            }
+            SimdWidth::Bits512 => {
+                score_freq_transposed::<16>(guesses, candidates, weights, wide::score_512)
+            }
+        }
+    }
+}
+
+fn score_freq_transposed<const LANES: usize>(
+    guesses: &[Word],
+    candidates: &[Word],
+    weights: &[f32],
+    kernel: Kernel<LANES>,
+) -> Vec<Histogram>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let words = SimdWords::from_words(candidates, weights);
+
+    map_guesses(guesses, |guess| kernel(&words, guess.fivegram.word))
+}
+
+/**
+    Padding lanes of the last chunk weigh nothing, whatever bin they land in
+**/
+#[inline(always)]
+fn score_transposed<const LANES: usize>(words: &[SimdWords<LANES>], guess: u32) -> Histogram
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let mut bins = [0.0; PATTERN_COUNT];
+    let guess = Simd::splat(guess);
+
+    for chunk in words {
+        let feedback = Feedback::score_simd(guess, chunk.word).to_array();
+        let weights = chunk.weights.to_array();
+        for lane in 0..LANES {
+            bins[feedback[lane] as usize] += weights[lane];
+        }
+    }
+
+    bins
+}
+
+/**
+    Same as in the `simd` backend, kernel is re-compiled with the wider
+    registers enabled
+**/
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod wide {
+    use super::score_transposed;
+    use crate::backend::Histogram;
+    use crate::simd_words::SimdWords;
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn score_avx2(words: &[SimdWords<8>], guess: u32) -> Histogram {
+        score_transposed(words, guess)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn score_avx512(words: &[SimdWords<16>], guess: u32) -> Histogram {
+        score_transposed(words, guess)
+    }
+
+    pub fn score_256(words: &[SimdWords<8>], guess: u32) -> Histogram {
+        unsafe { score_avx2(words, guess) }
+    }
+
+    pub fn score_512(words: &[SimdWords<16>], guess: u32) -> Histogram {
+        unsafe { score_avx512(words, guess) }
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+mod wide {
+    use super::score_transposed;
+    use crate::backend::Histogram;
+    use crate::simd_words::SimdWords;
+
+    pub fn score_256(words: &[SimdWords<8>], guess: u32) -> Histogram {
+        score_transposed(words, guess)
+    }
+
+    pub fn score_512(words: &[SimdWords<16>], guess: u32) -> Histogram {
+        score_transposed(words, guess)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core_simd::{LaneCount, SupportedLaneCount};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    use super::score_transposed;
+    use crate::backend::tests::{KNOWN_BINS, KNOWN_WORD};
+    use crate::simd_words::SimdWords;
+    use crate::Dictionary;
+
+    fn check_known_bins<const LANES: usize>()
+    where
+        LaneCount<LANES>: SupportedLaneCount,
+    {
+        let dictionary = Dictionary::bundled();
+        let words = dictionary.words();
+        let weights = vec![1.0; words.len()];
+
+        let sorel_idx = words
+            .iter()
+            .enumerate()
+            .find(|(_, w)| w.to_str() == KNOWN_WORD)
+            .unwrap()
+            .0;
+        let simd_words = SimdWords::<LANES>::from_words(words, &weights);
+
+        let bins: Vec<usize> = score_transposed(&simd_words, words[sorel_idx].fivegram.word)
+            .iter()
+            .map(|&x| x as usize)
+            .filter(|&x| x != 0)
+            .collect();
+
+        let bh: HashSet<usize> = HashSet::from_iter(bins);
+        let eh: HashSet<usize> = HashSet::from_iter(KNOWN_BINS);
+
+        assert_eq!(bh, eh)
+    }
+
+    #[test]
+    fn known_bins_transposed() {
+        check_known_bins::<4>();
+        check_known_bins::<8>();
+        check_known_bins::<16>();
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    extern crate test;
+
+    use test::Bencher;
+
+    use crate::backend::{EntropyBackend, SimdBackend, TransposedBackend};
+    use crate::Dictionary;
+
+    const BENCH_GUESSES: usize = 16;
+
+    fn bench_backend(b: &mut Bencher, backend: &dyn EntropyBackend) {
+        let dictionary = Dictionary::bundled();
+        let words = dictionary.words();
+        let weights = vec![1.0; words.len()];
+
+        b.iter(|| backend.histograms(&words[..BENCH_GUESSES], words, &weights));
+    }
+
+    #[bench]
+    fn bench_simd(b: &mut Bencher) {
+        bench_backend(b, &SimdBackend::detect());
+    }
+
+    #[bench]
+    fn bench_transposed(b: &mut Bencher) {
+        bench_backend(b, &TransposedBackend::detect());
+    }
+}
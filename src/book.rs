@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::GuessReport;
+use crate::{Dictionary, Feedback, Solver};
+
+/**
+    Precomputed rankings of a fixed opening: the opener at the root, then the
+    best next guesses for every feedback the game can give to it (and to the
+    best guess after that, as deep as it was built).
+
+    Each entry follows the first of its guesses, a game that guessed anything
+    else is out of the book.
+**/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Book {
+    /**
+        `Dictionary::fingerprint` of the dictionary it was built for
+    **/
+    pub dictionary: u64,
+    pub strategy: String,
+    pub root: BookEntry,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookEntry {
+    pub reports: Vec<GuessReport>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub replies: BTreeMap<Feedback, BookEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookError {
+    Dictionary,
+    Strategy { book: String, solver: String },
+}
+
+impl Display for BookError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookError::Dictionary => write!(f, "the book was built for another dictionary"),
+            BookError::Strategy { book, solver } => write!(
+                f,
+                "the book was built for the {} strategy, not {}",
+                book, solver
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+impl Book {
+    /**
+        Whether the rankings apply to the solver, the ones that don't are
+        never looked up
+    **/
+    pub fn check(&self, solver: &Solver) -> Result<(), BookError> {
+        if self.dictionary != solver.dictionary().fingerprint() {
+            return Err(BookError::Dictionary);
+        }
+        if self.strategy != solver.strategy().name() {
+            return Err(BookError::Strategy {
+                book: self.strategy.clone(),
+                solver: solver.strategy().name().to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+        Entry for the game so far, `None` once the game left the book
+    **/
+    pub fn lookup(
+        &self,
+        dictionary: &Dictionary,
+        history: &[(usize, Feedback)],
+    ) -> Option<&BookEntry> {
+        let mut entry = &self.root;
+        for &(guess, feedback) in history {
+            if entry.reports.first()?.word != dictionary.word(guess) {
+                return None;
+            }
+            entry = entry.replies.get(&feedback)?;
+        }
+
+        Some(entry)
+    }
+
+    /**
+        Number of the ranked positions, the root included
+    **/
+    pub fn entries(&self) -> usize {
+        fn count(entry: &BookEntry) -> usize {
+            1 + entry.replies.values().map(count).sum::<usize>()
+        }
+
+        count(&self.root)
+    }
+}
+
+fn build_entry(solver: &Solver, reports: Vec<GuessReport>, depth: usize, top: usize) -> BookEntry {
+    let mut replies = BTreeMap::new();
+
+    if depth > 1 {
+        let dictionary = solver.dictionary();
+        let guess = dictionary
+            .index_of(&reports[0].word)
+            .expect("Reported guess is in the dictionary");
+
+        // a single candidate is the answer, nothing to rank there
+        for (feedback, words) in solver.explain(guess) {
+            if feedback.is_solved() || words.len() < 2 {
+                continue;
+            }
+
+            let mut next = solver.clone();
+            next.apply_index(guess, feedback);
+            let reports = next.report(top);
+            replies.insert(feedback, build_entry(&next, reports, depth - 1, top));
+        }
+    }
+
+    BookEntry { reports, replies }
+}
+
+/**
+    Plays the opener from the solver state and ranks the `top` next guesses
+    for each of its feedback, `depth` counts the guesses (2 stops after the
+    second one, 3 follows the best second guesses for the third)
+**/
+pub fn build(solver: &Solver, opener: usize, depth: usize, top: usize) -> Book {
+    let mut solver = solver.clone();
+    solver.clear_book();
+
+    let opening = solver.guess_report(opener);
+
+    Book {
+        dictionary: solver.dictionary().fingerprint(),
+        strategy: solver.strategy().name().to_string(),
+        root: build_entry(&solver, vec![opening], depth.max(1), top),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::backend::CpuBackend;
+    use crate::book::{build, Book, BookError};
+    use crate::strategy::Minimax;
+    use crate::{Dictionary, Feedback, Solver};
+
+    fn solver() -> Solver {
+        let dictionary = Dictionary::parse(
+            "cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares\nsores\nsored\nbores",
+        )
+        .unwrap();
+
+        Solver::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    #[test]
+    fn build_and_lookup() {
+        let solver = solver();
+        let dictionary = solver.dictionary();
+        let humph = dictionary.index_of("humph").unwrap();
+        let book = build(&solver, humph, 2, 3);
+
+        assert_eq!(book.root.reports[0].word, "humph");
+        // only the buckets with more than one word are ranked
+        let grey: Feedback = "BBBBB".parse().unwrap();
+        assert_eq!(book.root.replies.keys().collect::<Vec<_>>(), vec![&grey]);
+        assert_eq!(book.entries(), 2);
+
+        let mut live = solver.clone();
+        live.apply_index(humph, grey);
+        let entry = book.lookup(dictionary, live.history()).unwrap();
+        assert_eq!(entry.reports, live.report(3));
+        assert!(entry.replies.is_empty());
+
+        let tares = dictionary.index_of("tares").unwrap();
+        assert!(book.lookup(dictionary, &[(tares, grey)]).is_none());
+
+        let json = serde_json::to_string(&book).unwrap();
+        assert_eq!(serde_json::from_str::<Book>(&json).unwrap(), book);
+    }
+
+    #[test]
+    fn solver_follows_the_book() {
+        let solver = solver();
+        let dictionary = solver.dictionary();
+        let tares = dictionary.index_of("tares").unwrap();
+        let book = Arc::new(build(&solver, tares, 3, 2));
+        assert_eq!(book.check(&solver), Ok(()));
+
+        let mut booked = solver.clone().with_book(book.clone());
+        assert_eq!(booked.top(1), vec![(tares, booked.report(1)[0].score)]);
+        assert_eq!(booked.report(1)[0].word, "tares");
+
+        let mut live = solver.clone();
+        // sores and bores
+        let feedback: Feedback = "BBGGG".parse().unwrap();
+        booked.apply_index(tares, feedback);
+        live.apply_index(tares, feedback);
+        assert!(book.lookup(dictionary, booked.history()).is_some());
+        assert_eq!(booked.report(2), live.report(2));
+        assert_eq!(booked.top(2), live.top(2));
+        // more than the book has is ranked live
+        assert_eq!(booked.report(3), live.report(3));
+
+        // humph is nobody's first choice, only the book plays it
+        let humph = dictionary.index_of("humph").unwrap();
+        let book = Arc::new(build(&solver, humph, 1, 1));
+        assert_eq!(solver.clone().with_book(book.clone()).top(1)[0].0, humph);
+
+        let minimax = solver.clone().with_strategy(Arc::new(Minimax));
+        assert!(matches!(
+            book.check(&minimax),
+            Err(BookError::Strategy { .. })
+        ));
+        assert_ne!(minimax.with_book(book.clone()).top(1)[0].0, humph);
+
+        let other = Solver::new(
+            Arc::new(Dictionary::parse("tares\nsorel").unwrap()),
+            Arc::new(CpuBackend),
+        );
+        assert_eq!(book.check(&other), Err(BookError::Dictionary));
+    }
+}
@@ -0,0 +1,438 @@
+use std::fmt::{Display, Formatter};
+
+use crate::fivegram::FIVEGRAM;
+use crate::{Dictionary, Feedback, Solver, Tile};
+
+/**
+    Feedback no answer could give, rows count from 1. `earlier` is the row
+    the broken constraint came from.
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contradiction {
+    /**
+        Yellow letter after a grey one of the same letter, the game colours
+        the first ones
+    **/
+    YellowAfterGrey {
+        row: usize,
+        letter: char,
+    },
+    /**
+        Two different green letters at the same position
+    **/
+    GreenConflict {
+        row: usize,
+        earlier: usize,
+        position: usize,
+        letter: char,
+        other: char,
+    },
+    /**
+        Green letter at the position where another row says it's not, or the
+        other way around
+    **/
+    NotAtPosition {
+        row: usize,
+        earlier: usize,
+        position: usize,
+        letter: char,
+    },
+    /**
+        More of the letter coloured than a grey tile allowed, grey earlier
+        and green now is the common typo
+    **/
+    LetterCount {
+        letter: char,
+        min: usize,
+        min_row: usize,
+        max: usize,
+        max_row: usize,
+    },
+    TooManyLetters {
+        row: usize,
+    },
+}
+
+impl Display for Contradiction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Contradiction::YellowAfterGrey { row, letter } => write!(
+                f,
+                "row {}: yellow `{}` after a grey one, the first ones are coloured",
+                row, letter
+            ),
+            Contradiction::GreenConflict {
+                row,
+                earlier,
+                position,
+                letter,
+                other,
+            } => write!(
+                f,
+                "row {}: green `{}` at position {}, row {} has a green `{}` there",
+                row, letter, position, earlier, other
+            ),
+            Contradiction::NotAtPosition {
+                row,
+                earlier,
+                position,
+                letter,
+            } => write!(
+                f,
+                "row {}: `{}` at position {} contradicts row {}",
+                row, letter, position, earlier
+            ),
+            Contradiction::LetterCount {
+                letter,
+                min,
+                min_row,
+                max,
+                max_row,
+            } => write!(
+                f,
+                "row {} needs at least {} `{}`, row {} allows at most {}",
+                min_row, min, letter, max_row, max
+            ),
+            Contradiction::TooManyLetters { row } => {
+                write!(
+                    f,
+                    "row {}: more letters known than {} positions",
+                    row, FIVEGRAM
+                )
+            }
+        }
+    }
+}
+
+/**
+    What the rows so far tell about the answer, with the row each fact came
+    from
+**/
+#[derive(Default)]
+struct Known {
+    green: [Option<(u8, usize)>; FIVEGRAM],
+    not_at: Vec<(usize, u8, usize)>,
+    min: [(usize, usize); 26],
+    max: [Option<(usize, usize)>; 26],
+}
+
+/**
+    First row of the history which contradicts itself or the rows before it,
+    without looking at the dictionary
+**/
+pub fn find_contradiction(
+    dictionary: &Dictionary,
+    history: &[(usize, Feedback)],
+) -> Option<Contradiction> {
+    contradiction_from(dictionary, history, 1)
+}
+
+/**
+    Same as `find_contradiction` with the rows counted from `first_row`
+**/
+fn contradiction_from(
+    dictionary: &Dictionary,
+    history: &[(usize, Feedback)],
+    first_row: usize,
+) -> Option<Contradiction> {
+    let bytes = dictionary.bytes();
+    let mut known = Known::default();
+
+    for (r, &(guess, feedback)) in history.iter().enumerate() {
+        let row = r + first_row;
+        let guess = &bytes[guess];
+        let tiles = feedback.tiles();
+
+        let mut grey_seen = [false; 26];
+        let mut coloured = [0; 26];
+        for i in 0..FIVEGRAM {
+            let l = (guess[i] - b'a') as usize;
+            match tiles[i] {
+                Tile::Grey => grey_seen[l] = true,
+                Tile::Yellow if grey_seen[l] => {
+                    return Some(Contradiction::YellowAfterGrey {
+                        row,
+                        letter: char::from(guess[i]),
+                    })
+                }
+                _ => coloured[l] += 1,
+            }
+        }
+
+        for i in 0..FIVEGRAM {
+            let letter = guess[i];
+            let position = i + 1;
+            match (tiles[i], known.green[i]) {
+                (Tile::Green, Some((other, earlier))) if other != letter => {
+                    return Some(Contradiction::GreenConflict {
+                        row,
+                        earlier,
+                        position,
+                        letter: char::from(letter),
+                        other: char::from(other),
+                    })
+                }
+                (Tile::Green, _) => {
+                    let excluded = known
+                        .not_at
+                        .iter()
+                        .find(|&&(p, l, _)| p == i && l == letter);
+                    if let Some(&(_, _, earlier)) = excluded {
+                        return Some(Contradiction::NotAtPosition {
+                            row,
+                            earlier,
+                            position,
+                            letter: char::from(letter),
+                        });
+                    }
+                    known.green[i] = Some((letter, row));
+                }
+                (_, Some((green, earlier))) if green == letter => {
+                    return Some(Contradiction::NotAtPosition {
+                        row,
+                        earlier,
+                        position,
+                        letter: char::from(letter),
+                    })
+                }
+                _ => known.not_at.push((i, letter, row)),
+            }
+        }
+
+        for l in 0..26 {
+            if coloured[l] > known.min[l].0 {
+                known.min[l] = (coloured[l], row);
+            }
+            if grey_seen[l] && known.max[l].map_or(true, |(max, _)| coloured[l] < max) {
+                known.max[l] = Some((coloured[l], row));
+            }
+
+            if let Some((max, max_row)) = known.max[l] {
+                let (min, min_row) = known.min[l];
+                if min > max {
+                    return Some(Contradiction::LetterCount {
+                        letter: char::from(l as u8 + b'a'),
+                        min,
+                        min_row,
+                        max,
+                        max_row,
+                    });
+                }
+            }
+        }
+
+        if known.min.iter().map(|&(min, _)| min).sum::<usize>() > FIVEGRAM {
+            return Some(Contradiction::TooManyLetters { row });
+        }
+    }
+
+    None
+}
+
+/**
+    Contradiction the feedback to the guess would bring into the solver game
+**/
+pub fn check_guess(solver: &Solver, guess: usize, feedback: Feedback) -> Option<Contradiction> {
+    let mut history = solver.history().to_vec();
+    history.push((guess, feedback));
+
+    find_contradiction(solver.dictionary(), &history)
+}
+
+/**
+    Contradiction the row would bring into the game before it's played. The
+    noisy solver weighs the rows against each other instead, so only a row
+    contradicting itself is rejected there.
+**/
+pub fn check_row(solver: &Solver, guess: usize, feedback: Feedback) -> Option<Contradiction> {
+    match solver.noise() {
+        Some(_) => contradiction_from(
+            solver.dictionary(),
+            &[(guess, feedback)],
+            solver.history().len() + 1,
+        ),
+        None => check_guess(solver, guess, feedback),
+    }
+}
+
+/**
+    Feedback differing from the given one in a single tile
+**/
+pub fn single_tile_changes(feedback: Feedback) -> impl Iterator<Item = Feedback> {
+    let tiles = feedback.tiles();
+
+    (0..FIVEGRAM).flat_map(move |i| {
+        [Tile::Green, Tile::Yellow, Tile::Grey]
+            .into_iter()
+            .filter(move |&t| t != tiles[i])
+            .map(move |t| {
+                let mut changed = tiles;
+                changed[i] = t;
+                Feedback::from_tiles(changed)
+            })
+    })
+}
+
+/**
+    Single tile correction of the feedback to the guess which keeps the game
+    consistent and some candidates in it, the one leaving the most. The fixes
+    are scored over the candidates, they never reach the dictionary's
+    `FeedbackIndex`.
+**/
+pub fn suggest_fix(solver: &Solver, guess: usize, feedback: Feedback) -> Option<Feedback> {
+    let bytes = solver.dictionary().bytes();
+
+    single_tile_changes(feedback)
+        .filter(|&fix| check_row(solver, guess, fix).is_none())
+        .map(|fix| {
+            let left = match solver.noise() {
+                Some(_) => {
+                    let mut fixed = solver.clone();
+                    fixed.apply_index(guess, fix);
+                    fixed.candidates().len()
+                }
+                None => solver
+                    .candidates()
+                    .iter()
+                    .filter(|&&c| Feedback::score(&bytes[guess], &bytes[c]) == fix)
+                    .count(),
+            };
+            (fix, left)
+        })
+        .filter(|&(_, candidates)| candidates > 0)
+        .max_by_key(|&(fix, candidates)| (candidates, std::cmp::Reverse(fix)))
+        .map(|(fix, _)| fix)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::backend::CpuBackend;
+    use crate::consistency::Contradiction;
+    use crate::consistency::{check_row, find_contradiction, single_tile_changes, suggest_fix};
+    use crate::{Dictionary, Feedback, Solver};
+
+    fn dictionary() -> Dictionary {
+        Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares\ngeese\nsores").unwrap()
+    }
+
+    fn contradiction(rows: &[(&str, &str)]) -> Option<Contradiction> {
+        let dictionary = dictionary();
+        let history: Vec<(usize, Feedback)> = rows
+            .iter()
+            .map(|(w, f)| (dictionary.index_of(w).unwrap(), f.parse().unwrap()))
+            .collect();
+
+        find_contradiction(&dictionary, &history)
+    }
+
+    #[test]
+    fn contradictions() {
+        assert_eq!(
+            contradiction(&[("tares", "BBGGY"), ("sores", "GGGGB")]),
+            None
+        );
+        assert_eq!(contradiction(&[("geese", "BYBGG")]), None);
+        assert_eq!(
+            contradiction(&[("geese", "BBYGG")]),
+            Some(Contradiction::YellowAfterGrey {
+                row: 1,
+                letter: 'e'
+            })
+        );
+        // `r` grey in tares, green in rebut
+        assert_eq!(
+            contradiction(&[("tares", "BBBGY"), ("rebut", "GYBBB")]),
+            Some(Contradiction::LetterCount {
+                letter: 'r',
+                min: 1,
+                min_row: 2,
+                max: 0,
+                max_row: 1
+            })
+        );
+        assert_eq!(
+            contradiction(&[("tares", "GBBBB"), ("sores", "GBBBB")]),
+            Some(Contradiction::GreenConflict {
+                row: 2,
+                earlier: 1,
+                position: 1,
+                letter: 's',
+                other: 't'
+            })
+        );
+        assert_eq!(
+            contradiction(&[("tares", "BBGGY"), ("sores", "GGYGB")]),
+            Some(Contradiction::NotAtPosition {
+                row: 2,
+                earlier: 1,
+                position: 3,
+                letter: 'r'
+            })
+        );
+        assert_eq!(
+            contradiction(&[("tares", "YYYYY"), ("humph", "YBBBB")]),
+            Some(Contradiction::TooManyLetters { row: 2 })
+        );
+    }
+
+    #[test]
+    fn single_tile_fix() {
+        assert_eq!(single_tile_changes(Feedback::SOLVED).count(), 10);
+
+        let dictionary = Arc::new(dictionary());
+        let mut solver = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let tares = dictionary.index_of("tares").unwrap();
+        solver.apply_index(tares, "BBGGY".parse().unwrap());
+
+        // the last `s` of sores mistyped as yellow
+        let sores = dictionary.index_of("sores").unwrap();
+        assert_eq!(
+            suggest_fix(&solver, sores, "GGGGY".parse().unwrap()),
+            Some("GGGGB".parse().unwrap())
+        );
+        let humph = dictionary.index_of("humph").unwrap();
+        assert_eq!(
+            suggest_fix(&solver, humph, "GBBBB".parse().unwrap()),
+            Some("BBBBB".parse().unwrap())
+        );
+
+        // the fixes tried on an opener aren't kept next to the real rows
+        let fresh = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        assert!(suggest_fix(&fresh, humph, "GBBBB".parse().unwrap()).is_some());
+        assert_eq!(dictionary.feedback_index().len(), 1);
+    }
+
+    #[test]
+    fn noisy_rows() {
+        let dictionary = Arc::new(dictionary());
+        let idx = |w: &str| dictionary.index_of(w).unwrap();
+        let mut exact = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let mut noisy = exact.clone().with_noise(0.05);
+        exact.apply_index(idx("tares"), "BBBGY".parse().unwrap());
+        noisy.apply_index(idx("tares"), "BBBGY".parse().unwrap());
+
+        // `r` grey in tares, green in rebut: outvoted when noisy
+        let rebut = (idx("rebut"), "GYBBB".parse().unwrap());
+        assert!(check_row(&exact, rebut.0, rebut.1).is_some());
+        assert_eq!(check_row(&noisy, rebut.0, rebut.1), None);
+
+        // the row contradicts itself either way
+        let geese = (idx("geese"), "BBYGG".parse().unwrap());
+        let yellow_after_grey = Contradiction::YellowAfterGrey {
+            row: 2,
+            letter: 'e',
+        };
+        assert_eq!(
+            check_row(&exact, geese.0, geese.1),
+            Some(yellow_after_grey.clone())
+        );
+        assert_eq!(check_row(&noisy, geese.0, geese.1), Some(yellow_after_grey));
+        assert_eq!(
+            suggest_fix(&noisy, geese.0, geese.1),
+            Some("BYYGG".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use std::sync::Arc;
+
+use crate::fivegram::FIVEGRAM;
+use crate::word::{wordbytes_from_str, wordbytes_to_str, WordBytes};
+use crate::word_set::FeedbackIndex;
+use crate::Word;
+
+/**
+    Bundled list starts with the original answers in the order they were
+    published, the rest of the allowed guesses follow
+**/
+pub const BUNDLED_ANSWERS: usize = 2315;
+
+/**
+    List of the allowed words, both as bytes and pre-computed `Word`s,
+    indices are shared between the two and used across the API
+**/
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    bytes: Vec<WordBytes>,
+    words: Vec<Word>,
+    index: HashMap<WordBytes, usize>,
+    fingerprint: u64,
+    feedback_index: Arc<FeedbackIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictionaryError {
+    InvalidWord { line: usize, word: String },
+    Empty,
+}
+
+impl Display for DictionaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictionaryError::InvalidWord { line, word } => write!(
+                f,
+                "line {}: `{}` is not a {}-letter lower-case word",
+                line, word, FIVEGRAM
+            ),
+            DictionaryError::Empty => write!(f, "dictionary has no words"),
+        }
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+impl Dictionary {
+    /**
+        Word list the binary is compiled with
+    **/
+    pub fn bundled() -> Self {
+        Self::parse(include_str!("../dict.txt")).expect("Bundled dictionary is malformed")
+    }
+
+    /**
+        One word per line, empty lines are skipped
+    **/
+    pub fn parse(text: &str) -> Result<Self, DictionaryError> {
+        let bytes = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line, word)| {
+                if word.len() == FIVEGRAM && word.bytes().all(|b| b.is_ascii_lowercase()) {
+                    Ok(wordbytes_from_str(word))
+                } else {
+                    Err(DictionaryError::InvalidWord {
+                        line,
+                        word: word.to_string(),
+                    })
+                }
+            })
+            .collect::<Result<Vec<WordBytes>, _>>()?;
+
+        if bytes.is_empty() {
+            return Err(DictionaryError::Empty);
+        }
+
+        Ok(Self::from_wordbytes(bytes))
+    }
+
+    pub fn from_wordbytes(bytes: Vec<WordBytes>) -> Self {
+        let words = bytes.iter().map(Word::from_wordbytes).collect();
+        let index = bytes.iter().enumerate().map(|(i, wb)| (*wb, i)).collect();
+        let fingerprint = bytes
+            .iter()
+            .flatten()
+            .fold(0xcbf29ce484222325u64, |hash, &b| {
+                (hash ^ b as u64).wrapping_mul(0x100000001b3)
+            });
+
+        Dictionary {
+            bytes,
+            words,
+            index,
+            fingerprint,
+            feedback_index: Arc::new(FeedbackIndex::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    pub fn bytes(&self) -> &[WordBytes] {
+        &self.bytes
+    }
+
+    pub fn word(&self, idx: usize) -> String {
+        wordbytes_to_str(&self.bytes[idx])
+    }
+
+    /**
+        FNV-1a hash of the words in their order, tells whether the files
+        computed for a dictionary (indices, books) still apply to it
+    **/
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /**
+        Cached feedback sets of the words, shared by every solver of the
+        dictionary (and of its clones)
+    **/
+    pub fn feedback_index(&self) -> &FeedbackIndex {
+        &self.feedback_index
+    }
+
+    pub fn index_of(&self, word: &str) -> Option<usize> {
+        let bytes: WordBytes = word.as_bytes().try_into().ok()?;
+
+        self.index.get(&bytes).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Dictionary, DictionaryError, BUNDLED_ANSWERS};
+
+    #[test]
+    fn bundled() {
+        let dictionary = Dictionary::bundled();
+
+        assert_eq!(dictionary.len(), 12972);
+        assert_eq!(dictionary.word(0), "cigar");
+        assert_eq!(
+            dictionary.index_of("sorel").map(|i| dictionary.word(i)),
+            Some("sorel".to_string())
+        );
+        assert_eq!(dictionary.words()[1].to_str(), "rebut");
+        assert_eq!(dictionary.word(BUNDLED_ANSWERS - 1), "shave");
+    }
+
+    #[test]
+    fn fingerprint() {
+        let a = Dictionary::parse("cigar\nrebut").unwrap();
+
+        assert_eq!(
+            a.fingerprint(),
+            Dictionary::parse("cigar\n\nrebut\n").unwrap().fingerprint()
+        );
+        assert_ne!(
+            a.fingerprint(),
+            Dictionary::parse("rebut\ncigar").unwrap().fingerprint()
+        );
+        assert_ne!(
+            a.fingerprint(),
+            Dictionary::parse("cigar").unwrap().fingerprint()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Dictionary::parse("cigar\nRebut\n").unwrap_err(),
+            DictionaryError::InvalidWord {
+                line: 2,
+                word: "Rebut".to_string()
+            }
+        );
+        assert_eq!(
+            Dictionary::parse("\n\n").unwrap_err(),
+            DictionaryError::Empty
+        );
+    }
+
+    #[test]
+    fn index_of_unknown() {
+        let dictionary = Dictionary::parse("cigar\nrebut").unwrap();
+
+        assert_eq!(dictionary.index_of("rebut"), Some(1));
+        assert_eq!(dictionary.index_of("sissy"), None);
+        assert_eq!(dictionary.index_of("toolong"), None);
+    }
+}
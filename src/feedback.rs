@@ -0,0 +1,611 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "simd")]
+use core_simd::{LaneCount, SupportedLaneCount};
+
+use crate::fivegram::FIVEGRAM;
+use crate::pattern::PATTERN_COUNT;
+#[cfg(feature = "simd")]
+use crate::simd_words::{Mask, Simd};
+use crate::word::WordBytes;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Green,
+    Yellow,
+    Grey,
+}
+
+/**
+    Colours of the five tiles, encoded the same way as the `Pattern` index:
+    base-3 digit per position, least significant first
+
+    0 - letter is at known position (green)
+    1 - letter present, but at wrong position (yellow)
+    2 - letter is absent in the word (grey)
+**/
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Feedback(u8);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseFeedbackError {
+    InvalidTile(char),
+    InvalidLength(usize),
+}
+
+impl Display for ParseFeedbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseFeedbackError::InvalidTile(c) => {
+                write!(f, "unknown tile `{}`, expected G/Y/B (or 🟩/🟨/⬛)", c)
+            }
+            ParseFeedbackError::InvalidLength(len) => {
+                write!(f, "expected {} tiles, got {}", FIVEGRAM, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseFeedbackError {}
+
+/**
+    First constraint of the feedback a word breaks, positions count from 1
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /**
+        Green tile, the word has another letter there
+    **/
+    GreenPosition {
+        position: usize,
+        letter: char,
+    },
+    /**
+        Letter known not to be at the position (yellow, or grey while the
+        others of it are coloured), the word has it there
+    **/
+    YellowPosition {
+        position: usize,
+        letter: char,
+    },
+    MissingLetter(char),
+    ForbiddenLetter(char),
+    /**
+        Repeated letters: coloured ones are the minimum, a grey one makes it
+        the exact number
+    **/
+    LetterCount {
+        letter: char,
+        min: usize,
+        exact: bool,
+        found: usize,
+    },
+    /**
+        The feedback contradicts itself, no word gives it to the guess
+    **/
+    Impossible,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::GreenPosition { position, letter } => {
+                write!(f, "needs `{}` at position {}", letter, position)
+            }
+            Violation::YellowPosition { position, letter } => {
+                write!(f, "can't have `{}` at position {}", letter, position)
+            }
+            Violation::MissingLetter(letter) => write!(f, "needs the letter `{}`", letter),
+            Violation::ForbiddenLetter(letter) => write!(f, "can't have the letter `{}`", letter),
+            Violation::LetterCount {
+                letter,
+                min,
+                exact,
+                found,
+            } => write!(
+                f,
+                "needs {} {} `{}`, has {}",
+                if *exact { "exactly" } else { "at least" },
+                min,
+                letter,
+                found
+            ),
+            Violation::Impossible => write!(f, "no word gives this feedback"),
+        }
+    }
+}
+
+impl Feedback {
+    pub const SOLVED: Feedback = Feedback(0);
+
+    pub fn from_index(idx: usize) -> Option<Self> {
+        (idx < PATTERN_COUNT).then(|| Feedback(idx as u8))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn from_tiles(tiles: [Tile; FIVEGRAM]) -> Self {
+        let idx = tiles.iter().rev().fold(0, |acc, tile| {
+            acc * 3
+                + match tile {
+                    Tile::Green => 0,
+                    Tile::Yellow => 1,
+                    Tile::Grey => 2,
+                }
+        });
+
+        Feedback(idx)
+    }
+
+    pub fn tiles(self) -> [Tile; FIVEGRAM] {
+        let mut tiles = [Tile::Grey; FIVEGRAM];
+        for (i, tile) in tiles.iter_mut().enumerate() {
+            *tile = match (self.0 as usize / 3usize.pow(i as u32)) % 3 {
+                0 => Tile::Green,
+                1 => Tile::Yellow,
+                2 => Tile::Grey,
+                _ => unreachable!(),
+            }
+        }
+
+        tiles
+    }
+
+    pub fn is_solved(self) -> bool {
+        self == Feedback::SOLVED
+    }
+
+    /**
+        Number of the tiles coloured differently
+    **/
+    pub fn distance(self, other: Feedback) -> usize {
+        self.tiles()
+            .iter()
+            .zip(other.tiles())
+            .filter(|&(a, b)| *a != b)
+            .count()
+    }
+
+    /**
+        Feedback the game gives for the guess, repeated letters are coloured
+        only as many times as they appear in the answer, greens first
+    **/
+    pub fn score(guess: &WordBytes, answer: &WordBytes) -> Self {
+        let mut tiles = [Tile::Grey; FIVEGRAM];
+        let mut unmatched = [0u8; 26];
+
+        for i in 0..FIVEGRAM {
+            if guess[i] == answer[i] {
+                tiles[i] = Tile::Green;
+            } else {
+                unmatched[(answer[i] - b'a') as usize] += 1;
+            }
+        }
+
+        for i in 0..FIVEGRAM {
+            let letter = (guess[i] - b'a') as usize;
+            if tiles[i] != Tile::Green && unmatched[letter] > 0 {
+                unmatched[letter] -= 1;
+                tiles[i] = Tile::Yellow;
+            }
+        }
+
+        Feedback::from_tiles(tiles)
+    }
+
+    /**
+        `score` of `LANES` pairs of `Fivegram` packed words at once, as the
+        feedback indices. A yellow takes the first answer letter that is
+        neither green nor taken by an earlier yellow.
+    **/
+    #[cfg(feature = "simd")]
+    #[inline]
+    pub fn score_simd<const LANES: usize>(guess: Simd<LANES>, answer: Simd<LANES>) -> Simd<LANES>
+    where
+        LaneCount<LANES>: SupportedLaneCount,
+    {
+        let letter_mask = Simd::splat(0b11111);
+        let letter = |word: Simd<LANES>, i: usize| {
+            (word >> Simd::splat((i * FIVEGRAM) as u32)) & letter_mask
+        };
+        let guess: [Simd<LANES>; FIVEGRAM] = [0, 1, 2, 3, 4].map(|i| letter(guess, i));
+        let answer: [Simd<LANES>; FIVEGRAM] = [0, 1, 2, 3, 4].map(|i| letter(answer, i));
+
+        let green: [Mask<LANES>; FIVEGRAM] = [0, 1, 2, 3, 4].map(|i| guess[i].lanes_eq(answer[i]));
+        let mut taken = green;
+        let mut index = Simd::splat(0);
+        let mut power = 1;
+
+        for i in 0..FIVEGRAM {
+            let mut yellow = Mask::splat(false);
+            for j in 0..FIVEGRAM {
+                let hit = !green[i] & !yellow & !taken[j] & guess[i].lanes_eq(answer[j]);
+                taken[j] |= hit;
+                yellow |= hit;
+            }
+
+            let tile = green[i].select(
+                Simd::splat(0),
+                yellow.select(Simd::splat(1), Simd::splat(2)),
+            );
+            index += tile * Simd::splat(power);
+            power *= 3;
+        }
+
+        index
+    }
+
+    /**
+        Why the word can't be the answer after the guess got this feedback,
+        `Ok` when it still can. Follows `score`, repeated letters included.
+    **/
+    pub fn check(self, guess: &WordBytes, word: &WordBytes) -> Result<(), Violation> {
+        if Feedback::score(guess, word) == self {
+            return Ok(());
+        }
+
+        let tiles = self.tiles();
+
+        for i in 0..FIVEGRAM {
+            if tiles[i] == Tile::Green && word[i] != guess[i] {
+                return Err(Violation::GreenPosition {
+                    position: i + 1,
+                    letter: char::from(guess[i]),
+                });
+            }
+        }
+        for i in 0..FIVEGRAM {
+            if tiles[i] == Tile::Yellow && word[i] == guess[i] {
+                return Err(Violation::YellowPosition {
+                    position: i + 1,
+                    letter: char::from(guess[i]),
+                });
+            }
+        }
+
+        let counts = |b: u8| {
+            let positions = || (0..FIVEGRAM).filter(move |&i| guess[i] == b);
+            let coloured = positions().filter(|&i| tiles[i] != Tile::Grey).count();
+            let grey = positions().any(|i| tiles[i] == Tile::Grey);
+            let found = word.iter().filter(|&&w| w == b).count();
+            (coloured, grey, found)
+        };
+        for &b in guess {
+            let (coloured, _, found) = counts(b);
+            if coloured > 0 && found == 0 {
+                return Err(Violation::MissingLetter(char::from(b)));
+            }
+            if coloured == 0 && found > 0 {
+                return Err(Violation::ForbiddenLetter(char::from(b)));
+            }
+        }
+        for &b in guess {
+            let (coloured, grey, found) = counts(b);
+            if found < coloured || (grey && found > coloured) {
+                return Err(Violation::LetterCount {
+                    letter: char::from(b),
+                    min: coloured,
+                    exact: grey,
+                    found,
+                });
+            }
+        }
+
+        // grey with the rest of the letter's count taken elsewhere, none of
+        // them is when the feedback couldn't be given in the first place
+        match (0..FIVEGRAM).find(|&i| word[i] == guess[i]) {
+            Some(position) => Err(Violation::YellowPosition {
+                position: position + 1,
+                letter: char::from(guess[position]),
+            }),
+            None => Err(Violation::Impossible),
+        }
+    }
+
+    pub fn to_emoji(self) -> String {
+        self.tiles()
+            .iter()
+            .map(|tile| match tile {
+                Tile::Green => '🟩',
+                Tile::Yellow => '🟨',
+                Tile::Grey => '⬛',
+            })
+            .collect()
+    }
+}
+
+impl Display for Feedback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s: String = self
+            .tiles()
+            .iter()
+            .map(|tile| match tile {
+                Tile::Green => 'G',
+                Tile::Yellow => 'Y',
+                Tile::Grey => 'B',
+            })
+            .collect();
+
+        write!(f, "{}", s)
+    }
+}
+
+/**
+    Accepts both letters (`G`, `Y` and `B`/`.`/`-`/`x` for grey, any case)
+    and the share-text squares (`🟩`, `🟨`, `⬛`/`⬜`, high contrast `🟧`, `🟦`)
+**/
+impl FromStr for Feedback {
+    type Err = ParseFeedbackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tiles = s
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'g' | 'G' | '🟩' | '🟧' => Ok(Tile::Green),
+                'y' | 'Y' | '🟨' | '🟦' => Ok(Tile::Yellow),
+                'b' | 'B' | '.' | '-' | 'x' | 'X' | '⬛' | '⬜' => Ok(Tile::Grey),
+                c => Err(ParseFeedbackError::InvalidTile(c)),
+            })
+            .collect::<Result<Vec<Tile>, _>>()?;
+
+        let len = tiles.len();
+        let tiles: [Tile; FIVEGRAM] = tiles
+            .try_into()
+            .map_err(|_| ParseFeedbackError::InvalidLength(len))?;
+
+        Ok(Feedback::from_tiles(tiles))
+    }
+}
+
+/**
+    Serialized as the `GYB` string, parsed with `FromStr` so emoji work too
+**/
+impl Serialize for Feedback {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Feedback {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    use crate::feedback::{Feedback, ParseFeedbackError, Tile, Violation};
+    use crate::pattern::PATTERN_COUNT;
+    use crate::word::wordbytes_from_str;
+    use crate::{Pattern, Word};
+
+    fn score(guess: &str, answer: &str) -> String {
+        Feedback::score(&wordbytes_from_str(guess), &wordbytes_from_str(answer)).to_string()
+    }
+
+    #[test]
+    fn score_distinct_letters() {
+        assert_eq!(score("tares", "sorel"), "BBGGY");
+        assert_eq!(score("sorel", "sorel"), "GGGGG");
+        assert_eq!(score("cigar", "humph"), "BBBBB");
+    }
+
+    #[test]
+    fn score_repeated_letters() {
+        // only one `s` in the answer, green takes it
+        assert_eq!(score("sissy", "sorel"), "GBBBB");
+        // two `e` in the guess, one in the answer, first one is yellow
+        assert_eq!(score("geese", "those"), "BBBGG");
+        assert_eq!(score("eerie", "abbey"), "YBBBB");
+    }
+
+    fn check(guess: &str, feedback: &str, word: &str) -> Result<(), Violation> {
+        feedback
+            .parse::<Feedback>()
+            .unwrap()
+            .check(&wordbytes_from_str(guess), &wordbytes_from_str(word))
+    }
+
+    #[test]
+    fn violations() {
+        assert_eq!(check("tares", "BBGGY", "sorel"), Ok(()));
+        assert_eq!(
+            check("tares", "BBGGY", "shoes"),
+            Err(Violation::GreenPosition {
+                position: 3,
+                letter: 'r'
+            })
+        );
+        assert_eq!(
+            check("tares", "BBGGY", "shres"),
+            Err(Violation::YellowPosition {
+                position: 5,
+                letter: 's'
+            })
+        );
+        assert_eq!(
+            check("tares", "BBGGY", "bored"),
+            Err(Violation::MissingLetter('s'))
+        );
+        assert_eq!(
+            check("tares", "BBGGY", "soret"),
+            Err(Violation::ForbiddenLetter('t'))
+        );
+        // one `e` of geese is green, the grey ones cap it at exactly one
+        assert_eq!(
+            check("geese", "BBBGG", "eerie"),
+            Err(Violation::GreenPosition {
+                position: 4,
+                letter: 's'
+            })
+        );
+        assert_eq!(check("geese", "BBBGG", "those"), Ok(()));
+        assert_eq!(
+            check("geese", "BBBGG", "elsse"),
+            Err(Violation::LetterCount {
+                letter: 'e',
+                min: 1,
+                exact: true,
+                found: 2
+            })
+        );
+        assert_eq!(
+            check("bacaa", "YYYYB", "acbba"),
+            Err(Violation::YellowPosition {
+                position: 5,
+                letter: 'a'
+            })
+        );
+        assert_eq!(
+            Violation::LetterCount {
+                letter: 'e',
+                min: 2,
+                exact: false,
+                found: 1
+            }
+            .to_string(),
+            "needs at least 2 `e`, has 1"
+        );
+        // yellow `l` after a grey one, noisy games keep such rows
+        assert_eq!(check("llama", "BYBBB", "world"), Err(Violation::Impossible));
+    }
+
+    // Any feedback, the ones no answer gives included
+    #[test]
+    fn proptest_check_any_feedback() {
+        proptest!(|(guess in "[a-e]{5}", feedback in 0..PATTERN_COUNT, word in "[a-e]{5}")| {
+            let (guess, word) = (wordbytes_from_str(&guess), wordbytes_from_str(&word));
+            let feedback = Feedback::from_index(feedback).unwrap();
+
+            assert_eq!(
+                feedback.check(&guess, &word).is_ok(),
+                Feedback::score(&guess, &word) == feedback
+            );
+        });
+    }
+
+    // Every word with a different feedback breaks one of the constraints
+    #[test]
+    fn proptest_check_matches_score() {
+        proptest!(|(guess in "[a-e]{5}", answer in "[a-e]{5}", word in "[a-e]{5}")| {
+            let (guess, answer, word) = (
+                wordbytes_from_str(&guess),
+                wordbytes_from_str(&answer),
+                wordbytes_from_str(&word),
+            );
+            let feedback = Feedback::score(&guess, &answer);
+
+            assert_eq!(
+                feedback.check(&guess, &word).is_ok(),
+                Feedback::score(&guess, &word) == feedback
+            );
+        });
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("gy.b-".parse::<Feedback>().unwrap().to_string(), "GYBBB");
+        assert_eq!(
+            "🟩🟨⬛⬜🟩".parse::<Feedback>().unwrap().to_string(),
+            "GYBBG"
+        );
+        assert_eq!(
+            "🟧🟦⬛⬛🟧".parse::<Feedback>().unwrap().to_string(),
+            "GYBBG"
+        );
+        assert_eq!(
+            "GYZBB".parse::<Feedback>(),
+            Err(ParseFeedbackError::InvalidTile('Z'))
+        );
+        assert_eq!(
+            "GY".parse::<Feedback>(),
+            Err(ParseFeedbackError::InvalidLength(2))
+        );
+    }
+
+    #[test]
+    fn serde_as_string() {
+        let feedback: Feedback = serde_json::from_str("\"🟩🟨⬛⬛⬛\"").unwrap();
+
+        assert_eq!(serde_json::to_string(&feedback).unwrap(), "\"GYBBB\"");
+        assert!(serde_json::from_str::<Feedback>("\"GYB\"").is_err());
+    }
+
+    #[test]
+    fn tiles_round_trip() {
+        for idx in 0..243 {
+            let feedback = Feedback::from_index(idx).unwrap();
+            assert_eq!(Feedback::from_tiles(feedback.tiles()), feedback);
+        }
+        assert_eq!(Feedback::from_index(243), None);
+        assert_eq!(Feedback::SOLVED.tiles(), [Tile::Green; 5]);
+
+        let feedback: Feedback = "BYGGB".parse().unwrap();
+        assert_eq!(feedback.distance(feedback), 0);
+        assert_eq!(feedback.distance(Feedback::SOLVED), 3);
+    }
+
+    // For the words without repeated letters the pattern of the same index
+    // should be matching the answer
+    #[test]
+    fn proptest_score_matches_pattern() {
+        proptest!(|(guess in "[a-z]{5}", answer in "[a-z]{5}")| {
+            prop_assume!(HashSet::<char>::from_iter(guess.chars()).len() == 5);
+
+            let feedback = Feedback::score(&wordbytes_from_str(&guess), &wordbytes_from_str(&answer));
+            let patterns = Pattern::from_bytes(&wordbytes_from_str(&guess));
+
+            assert!(patterns[feedback.index()].matches_word(&Word::from_str(&answer)));
+        });
+    }
+
+    #[cfg(feature = "simd")]
+    fn check_simd_score<const LANES: usize>()
+    where
+        core_simd::LaneCount<LANES>: core_simd::SupportedLaneCount,
+    {
+        use crate::simd_words::Simd;
+        use proptest::collection::vec;
+
+        // few letters for plenty of repeated ones
+        proptest!(|(guesses in vec("[a-e]{5}", LANES), answers in vec("[a-e]{5}", LANES))| {
+            let packed = |words: &[String]| {
+                let mut lanes = [0; LANES];
+                for (lane, w) in lanes.iter_mut().zip(words) {
+                    *lane = Word::from_str(w).fivegram.word;
+                }
+                Simd::from_array(lanes)
+            };
+
+            let simd = Feedback::score_simd(packed(&guesses), packed(&answers)).to_array();
+            let scalar: Vec<u32> = guesses
+                .iter()
+                .zip(&answers)
+                .map(|(g, a)| {
+                    Feedback::score(&wordbytes_from_str(g), &wordbytes_from_str(a)).index() as u32
+                })
+                .collect();
+            assert_eq!(simd.to_vec(), scalar);
+        });
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn proptest_simd_score() {
+        check_simd_score::<4>();
+        check_simd_score::<8>();
+        check_simd_score::<16>();
+    }
+}
@@ -1,4 +1,3 @@
-use crate::simd_pattern::{Mask, Simd};
 use std::fmt::{Display, Formatter};
 
 pub const FIVEGRAM: usize = 5;
@@ -60,11 +59,6 @@ impl Fivegram {
         self.word & pattern.letter_mask ^ pattern.word == 0
     }
 
-    #[inline]
-    pub fn exact_match_simd(word: &Simd, letter_mask: &Simd, pattern: &Simd) -> Simd {
-        word & letter_mask ^ pattern
-    }
-
     #[inline]
     pub fn any_pos_match(&self, pattern: &Self) -> bool {
         let intersection =
@@ -76,27 +70,6 @@ impl Fivegram {
             || intersection >> 15 & 0b11111 == 0
             || intersection >> 20 & 0b11111 == 0
     }
-
-    #[inline]
-    pub fn any_pos_match_simd(word: &Simd, letter_mask: &Simd, pattern: &Simd) -> Mask {
-        let intersection = (word & letter_mask ^ pattern) | !letter_mask.clone();
-
-        let mut acc = Mask::splat(false);
-        let zeros = Simd::splat(0);
-        let first_five_mask = Simd::splat(0b11111);
-
-        for shift in [
-            Simd::splat(0),
-            Simd::splat(5),
-            Simd::splat(10),
-            Simd::splat(15),
-            Simd::splat(20),
-        ] {
-            acc |= ((intersection >> shift) & first_five_mask).lanes_eq(zeros);
-        }
-
-        acc
-    }
 }
 
 #[cfg(test)]
@@ -104,9 +77,6 @@ mod tests {
     use pretty_assertions::assert_eq;
     use proptest::prelude::*;
 
-    use crate::simd_pattern::Simd;
-    use crate::simd_pattern::SIMD_WIDTH;
-
     use super::Fivegram;
 
     fn fivegram_from_pattern(pattern: &str) -> Fivegram {
@@ -120,25 +90,6 @@ mod tests {
         fg
     }
 
-    #[test]
-    fn proptest_exact_match_simd() {
-        proptest!(|(word in "[a-z]{5}", pattern in ["[a-z_]{5}"; SIMD_WIDTH])| {
-            let expected: [bool; SIMD_WIDTH] = pattern.iter().map(|p| {
-                word.chars().zip(p.chars()).all(|(l, r)| l == r || r == '_')
-            }).collect::<Vec<bool>>().try_into().unwrap();
-
-            let word = Simd::splat(Fivegram::from_bytes(word.as_bytes()).word);
-
-            let pattern = pattern.map(|p| fivegram_from_pattern(&p));
-            let pattern_words = Simd::from_array(pattern.map(|fg| fg.word));
-            let pattern_masks = Simd::from_array(pattern.map(|fg| fg.letter_mask));
-
-            let res = Fivegram::exact_match_simd(&word, &pattern_masks, &pattern_words);
-            let zeros = Simd::splat(0);
-            assert_eq!(res.lanes_eq(zeros).to_array(), expected)
-        })
-    }
-
     #[test]
     fn proptest_exact_match() {
         proptest!(|(word in "[a-z]{5}", pattern in "[a-z_]{5}")| {
@@ -153,24 +104,6 @@ mod tests {
         });
     }
 
-    #[test]
-    fn proptest_any_pos_match_simd() {
-        proptest!(|(word in "[a-z]{5}", pattern in ["[a-z_]{5}"; SIMD_WIDTH])| {
-            let expected: [bool; SIMD_WIDTH] = pattern.iter().map(|p| {
-                word.chars().zip(p.chars()).any(|(l, r)| l == r && r != '_')
-            }).collect::<Vec<bool>>().try_into().unwrap();
-
-            let word = Simd::splat(Fivegram::from_bytes(word.as_bytes()).word);
-
-            let pattern = pattern.map(|p| fivegram_from_pattern(&p));
-            let pattern_words = Simd::from_array(pattern.map(|fg| fg.word));
-            let pattern_masks = Simd::from_array(pattern.map(|fg| fg.letter_mask));
-
-            let res = Fivegram::any_pos_match_simd(&word, &pattern_masks, &pattern_words);
-            assert_eq!(res.to_array(), expected)
-        })
-    }
-
     #[test]
     fn proptest_any_pos_match() {
         proptest!(|(word in "[a-z]{5}", pattern in "[a-z_]{5}")| {
@@ -0,0 +1,432 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, ErrorKind, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dictionary::BUNDLED_ANSWERS;
+use crate::share::ShareGrid;
+use crate::simulate::Game;
+use crate::{Dictionary, Feedback};
+
+pub const MAX_GUESSES: usize = 6;
+
+/**
+    Days from 1970-01-01 to the date of the proleptic Gregorian calendar
+**/
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/**
+    Puzzle number of the date (`YYYY-MM-DD`), puzzle 0 was out on 2021-06-19
+**/
+pub fn puzzle_of_date(date: &str) -> Option<u32> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day) - days_from_civil(2021, 6, 19);
+    u32::try_from(days).ok()
+}
+
+/**
+    Puzzle of the current UTC day, no network needed
+**/
+pub fn puzzle_of_today() -> u32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    (secs as i64 / 86400 - days_from_civil(2021, 6, 19)).max(0) as u32
+}
+
+/**
+    Bundled answers are in publication order, the list starts over once it
+    runs out
+**/
+pub fn daily_answer(puzzle: u32) -> usize {
+    puzzle as usize % BUNDLED_ANSWERS
+}
+
+/**
+    Answer picked by the seed (splitmix64), same seed same answer
+**/
+pub fn random_answer(seed: u64) -> usize {
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+
+    (z % BUNDLED_ANSWERS as u64) as usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    InvalidLength(usize),
+    UnknownWord(String),
+    GameOver,
+}
+
+impl Display for GuessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuessError::InvalidLength(len) => write!(f, "expected 5 letters, got {}", len),
+            GuessError::UnknownWord(word) => write!(f, "`{}` is not in the word list", word),
+            GuessError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+/**
+    Game of a human against the secret answer, `puzzle` is the number of the
+    daily puzzle (`None` for the seeded games)
+**/
+pub struct Play<'a> {
+    dictionary: &'a Dictionary,
+    puzzle: Option<u32>,
+    game: Game,
+}
+
+impl<'a> Play<'a> {
+    pub fn new(dictionary: &'a Dictionary, answer: usize, puzzle: Option<u32>) -> Self {
+        Play {
+            dictionary,
+            puzzle,
+            game: Game {
+                answer,
+                guesses: vec![],
+            },
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game.is_solved() || self.game.turns() >= MAX_GUESSES
+    }
+
+    /**
+        Scores the guess against the answer, only the dictionary words count
+    **/
+    pub fn guess(&mut self, word: &str) -> Result<Feedback, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+
+        let word = word.trim().to_lowercase();
+        if word.chars().count() != 5 {
+            return Err(GuessError::InvalidLength(word.chars().count()));
+        }
+        let guess = self
+            .dictionary
+            .index_of(&word)
+            .ok_or(GuessError::UnknownWord(word))?;
+
+        let bytes = self.dictionary.bytes();
+        let feedback = Feedback::score(&bytes[guess], &bytes[self.game.answer]);
+        self.game.guesses.push((guess, feedback));
+
+        Ok(feedback)
+    }
+
+    pub fn share(&self) -> ShareGrid {
+        ShareGrid {
+            puzzle: self.puzzle,
+            hard_mode: false,
+            rows: self.game.guesses.iter().map(|&(_, f)| f).collect(),
+        }
+    }
+
+    /**
+        Prompts for the guesses until the game is over or the input ends
+    **/
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> std::io::Result<()> {
+        let mut lines = input.lines();
+
+        while !self.is_over() {
+            write!(out, "Guess {}/{}: ", self.game.turns() + 1, MAX_GUESSES)?;
+            out.flush()?;
+
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => break,
+            };
+            match self.guess(&line) {
+                Ok(feedback) => writeln!(out, "{} {}", feedback.to_emoji(), line.trim())?,
+                Err(err) => writeln!(out, "{}", err)?,
+            }
+        }
+
+        if self.game.is_solved() {
+            writeln!(out, "Solved in {}", self.game.turns())?;
+        } else {
+            writeln!(
+                out,
+                "The answer was {}",
+                self.dictionary.word(self.game.answer)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+    Results kept between the games, the daily puzzles also track the streak
+    of consecutive days
+**/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /**
+        Won games by the number of guesses
+    **/
+    pub distribution: [u32; MAX_GUESSES],
+    /**
+        Latest daily puzzle played, the streak follows it
+    **/
+    pub last_puzzle: Option<u32>,
+    /**
+        Every daily puzzle counted, the replays of older dates included
+    **/
+    #[serde(default)]
+    pub puzzles: BTreeSet<u32>,
+}
+
+impl Stats {
+    /**
+        Stats saved at the path, the empty ones when there's no file yet.
+        A file that can't be read or parsed is an error, so that it isn't
+        overwritten.
+    **/
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Stats::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /**
+        Counts the finished game, returns `false` for the daily puzzle that's
+        already counted. A replay of an older puzzle is counted without
+        touching the streak.
+    **/
+    pub fn record(&mut self, puzzle: Option<u32>, game: &Game) -> bool {
+        if let Some(p) = puzzle {
+            // the files from before `puzzles` only know the last one
+            if !self.puzzles.insert(p) || Some(p) == self.last_puzzle {
+                return false;
+            }
+        }
+
+        let latest = match (puzzle, self.last_puzzle) {
+            (Some(p), Some(last)) => p > last,
+            _ => true,
+        };
+        let missed_day = match (puzzle, self.last_puzzle) {
+            (Some(p), Some(last)) => p > last + 1,
+            _ => false,
+        };
+        if missed_day {
+            self.current_streak = 0;
+        }
+
+        self.played += 1;
+        if game.is_solved() {
+            self.won += 1;
+            self.distribution[game.turns() - 1] += 1;
+        }
+        if latest {
+            if game.is_solved() {
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+            } else {
+                self.current_streak = 0;
+            }
+            if puzzle.is_some() {
+                self.last_puzzle = puzzle;
+            }
+        }
+
+        true
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let won = if self.played == 0 {
+            0
+        } else {
+            self.won * 100 / self.played
+        };
+        writeln!(
+            f,
+            "Played {}, won {}%, streak {} (max {})",
+            self.played, won, self.current_streak, self.max_streak
+        )?;
+
+        let most = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (turns, &n) in self.distribution.iter().enumerate() {
+            let bar = "#".repeat((n * 20 / most) as usize);
+            writeln!(
+                f,
+                "{}",
+                format!("{} {:>4} {}", turns + 1, n, bar).trim_end()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::io::ErrorKind;
+
+    use crate::dictionary::BUNDLED_ANSWERS;
+    use crate::game::{
+        daily_answer, puzzle_of_date, random_answer, GuessError, Play, Stats, MAX_GUESSES,
+    };
+    use crate::Dictionary;
+
+    #[test]
+    fn secret_answers() {
+        assert_eq!(puzzle_of_date("2021-06-19"), Some(0));
+        assert_eq!(puzzle_of_date("2022-02-19"), Some(245));
+        assert_eq!(puzzle_of_date("2024-03-01"), Some(986));
+        assert_eq!(puzzle_of_date("2021-06-18"), None);
+        assert_eq!(puzzle_of_date("2022-13-01"), None);
+        assert_eq!(puzzle_of_date("today"), None);
+
+        let dictionary = Dictionary::bundled();
+        assert_eq!(dictionary.word(daily_answer(0)), "cigar");
+        assert_eq!(daily_answer(BUNDLED_ANSWERS as u32), 0);
+        assert_eq!(random_answer(7), random_answer(7));
+        assert!((0..100).all(|seed| random_answer(seed) < BUNDLED_ANSWERS));
+    }
+
+    #[test]
+    fn play_and_share() {
+        let dictionary = Dictionary::bundled();
+        let answer = dictionary.index_of("sorel").unwrap();
+        let mut play = Play::new(&dictionary, answer, Some(245));
+
+        let mut out = vec![];
+        play.run("tares\nxyzzy\nsor\nsorel\n".as_bytes(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            vec![
+                "Guess 1/6: ⬛⬛🟩🟩🟨 tares",
+                "Guess 2/6: `xyzzy` is not in the word list",
+                "Guess 2/6: expected 5 letters, got 3",
+                "Guess 2/6: 🟩🟩🟩🟩🟩 sorel",
+                "Solved in 2",
+            ]
+        );
+        assert_eq!(play.guess("tares"), Err(GuessError::GameOver));
+        assert_eq!(
+            play.share().to_string(),
+            "Wordle 245 2/6\n\n⬛⬛🟩🟩🟨\n🟩🟩🟩🟩🟩\n"
+        );
+    }
+
+    #[test]
+    fn stats_and_streaks() {
+        let dictionary = Dictionary::bundled();
+        let game = |guesses: &[&str]| {
+            let mut play = Play::new(&dictionary, 0, None);
+            for g in guesses {
+                play.guess(g).unwrap();
+            }
+            play.game().clone()
+        };
+        let won = game(&["tares", "cigar"]);
+        let lost = game(&["tares"; MAX_GUESSES]);
+
+        let mut stats = Stats::default();
+        assert!(stats.record(Some(10), &won));
+        assert!(!stats.record(Some(10), &won));
+        assert!(stats.record(Some(11), &won));
+        assert_eq!((stats.current_streak, stats.max_streak), (2, 2));
+
+        // skipped a day
+        assert!(stats.record(Some(13), &won));
+        assert_eq!((stats.current_streak, stats.max_streak), (1, 2));
+
+        assert!(stats.record(None, &lost));
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.played, 4);
+        assert_eq!(stats.won, 3);
+        assert_eq!(stats.distribution, [0, 3, 0, 0, 0, 0]);
+        assert_eq!(stats.last_puzzle, Some(13));
+    }
+
+    #[test]
+    fn stats_out_of_order() {
+        let dictionary = Dictionary::bundled();
+        let mut play = Play::new(&dictionary, 0, None);
+        play.guess("cigar").unwrap();
+        let won = play.game().clone();
+
+        let mut stats = Stats::default();
+        assert!(stats.record(Some(20), &won));
+        assert!(stats.record(Some(21), &won));
+        // `--date` replay of an older puzzle
+        assert!(stats.record(Some(5), &won));
+        assert_eq!((stats.current_streak, stats.last_puzzle), (2, Some(21)));
+        assert!(!stats.record(Some(5), &won));
+        assert!(!stats.record(Some(20), &won));
+        assert!(stats.record(Some(22), &won));
+        assert_eq!((stats.current_streak, stats.max_streak), (3, 3));
+        assert_eq!(stats.played, 4);
+
+        // saved before the played puzzles were kept
+        let mut old: Stats = serde_json::from_str(
+            &serde_json::to_string(&stats)
+                .unwrap()
+                .replace(r#""puzzles":[5,20,21,22]"#, r#""puzzles":[]"#),
+        )
+        .unwrap();
+        assert!(old.puzzles.is_empty());
+        assert!(!old.record(Some(22), &won));
+    }
+
+    #[test]
+    fn stats_file() {
+        let path = std::env::temp_dir().join(format!("wordle-stats-{}.json", std::process::id()));
+        assert_eq!(Stats::load(&path).unwrap(), Stats::default());
+
+        std::fs::write(&path, "{\"played\": ").unwrap();
+        let err = Stats::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,52 @@
+//! Information optimal Wordle solver.
+//!
+//! `Dictionary` holds the word list, `Feedback` is what the game answers to a
+//! guess, `Solver` keeps track of the game and ranks the next guesses with a
+//! `Strategy` over the pattern histograms built by one of the `EntropyBackend`s.
+//!
+//! Portable SIMD backends need nightly and sit behind the `simd` feature,
+//! the rust-gpu one behind `gpu`, without them the crate builds on stable.
+
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+#![cfg_attr(feature = "simd", feature(avx512_target_feature))]
+#![cfg_attr(all(test, feature = "simd"), feature(test))]
+
+pub use ascii_bit_set::AsciiBitSet;
+pub use backend::{BackendKind, EntropyBackend, Histogram};
+pub use dictionary::Dictionary;
+pub use feedback::{Feedback, Tile};
+pub use fivegram::Fivegram;
+pub use pattern::Pattern;
+pub use solver::Solver;
+pub use strategy::{Strategy, StrategyKind};
+pub use word::Word;
+
+pub mod analysis;
+pub mod api;
+pub mod ascii_bit_set;
+pub mod backend;
+pub mod book;
+pub mod consistency;
+pub mod dictionary;
+pub mod feedback;
+pub mod fivegram;
+pub mod game;
+pub mod pattern;
+pub mod repl;
+pub mod report;
+pub mod rpc;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+#[cfg(feature = "gpu")]
+pub mod shader;
+pub mod share;
+#[cfg(feature = "simd")]
+pub mod simd_words;
+pub mod simulate;
+pub mod solver;
+pub mod strategy;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod word;
+pub mod word_set;
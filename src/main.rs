@@ -1,41 +1,192 @@
-#![feature(portable_simd)]
-#![feature(int_roundings)]
-
-extern crate core;
-
-use clap::{ArgEnum, Parser};
-use rayon::prelude::*;
-
-use ascii_bit_set::AsciiBitSet;
-use fivegram::Fivegram;
-use pattern::{Pattern, Patterns};
-use word::Word;
-
-use crate::pattern::PATTERN_COUNT;
-use crate::simd_pattern::{FreqSimd, SimdPattern, SimdPatterns, SIMD_WIDTH};
-use crate::word::{wordbytes_from_str, wordbytes_to_str, WordBytes};
-
-mod ascii_bit_set;
-mod fivegram;
-mod pattern;
-mod shader;
-mod simd_pattern;
-mod word;
-
-const WORD_COUNT: usize = 12972;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::{ArgEnum, Parser, Subcommand};
+
+use wordle_solver::analysis::analyze;
+use wordle_solver::api::{Api, ExplainResponse};
+use wordle_solver::book::{build, Book};
+use wordle_solver::dictionary::BUNDLED_ANSWERS;
+use wordle_solver::game::{
+    daily_answer, puzzle_of_date, puzzle_of_today, random_answer, Play, Stats,
+};
+use wordle_solver::repl::Repl;
+use wordle_solver::report::{write_reports, Format};
+use wordle_solver::rpc::RpcServer;
+use wordle_solver::session::Session;
+use wordle_solver::share::{infer_answers, reconstruct, ShareGrid};
+use wordle_solver::{BackendKind, Dictionary, EntropyBackend, Solver, StrategyKind};
 
 #[derive(ArgEnum, Clone)]
 enum Implementation {
+    Auto,
     CPU,
     SIMD,
+    Transposed,
     GPU,
 }
 
+impl From<Implementation> for BackendKind {
+    fn from(implementation: Implementation) -> Self {
+        match implementation {
+            Implementation::Auto => BackendKind::Auto,
+            Implementation::CPU => BackendKind::Cpu,
+            Implementation::SIMD => BackendKind::Simd,
+            Implementation::Transposed => BackendKind::Transposed,
+            Implementation::GPU => BackendKind::Gpu,
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone)]
+enum StrategyArg {
+    Entropy,
+    Minimax,
+    ExpectedSize,
+    MostBuckets,
+    Frequency,
+}
+
+impl From<StrategyArg> for StrategyKind {
+    fn from(strategy: StrategyArg) -> Self {
+        match strategy {
+            StrategyArg::Entropy => StrategyKind::Entropy,
+            StrategyArg::Minimax => StrategyKind::Minimax,
+            StrategyArg::ExpectedSize => StrategyKind::ExpectedSize,
+            StrategyArg::MostBuckets => StrategyKind::MostBuckets,
+            StrategyArg::Frequency => StrategyKind::Frequency,
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone)]
+enum FormatArg {
+    Text,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl From<FormatArg> for Format {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Text => Format::Text,
+            FormatArg::Json => Format::Json,
+            FormatArg::Csv => Format::Csv,
+            FormatArg::Ndjson => Format::Ndjson,
+        }
+    }
+}
+
 #[derive(Parser)]
 #[clap(version = "0.1", author = "Andrew Korzhuev <korzhuev@andrusha.me>")]
 struct Cli {
-    #[clap(arg_enum)]
+    #[clap(arg_enum, default_value = "auto")]
     implementation: Implementation,
+
+    /// How to score the guesses
+    #[clap(long, arg_enum, default_value = "entropy", global = true)]
+    strategy: StrategyArg,
+
+    /// Number of the best guesses to show
+    #[clap(long, default_value = "10", global = true)]
+    top: usize,
+
+    /// Rank the whole dictionary instead of the top choices
+    #[clap(long)]
+    all: bool,
+
+    /// Output format, machine-readable ones include the bucket metrics
+    #[clap(long, arg_enum, default_value = "text")]
+    format: FormatArg,
+
+    /// Opening book (see `book build`) to look the rankings up in first
+    #[clap(long, global = true)]
+    book: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the JSON API (`POST /suggest`, `POST /score`) over HTTP
+    Serve {
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+    /// Line-delimited JSON-RPC on stdin/stdout, for bots and editors
+    Rpc,
+    /// Interactive solver: enter guesses with their feedback, get the next best guesses
+    Solve {
+        /// Full-screen board with coloured tiles instead of the prompt
+        #[clap(long)]
+        tui: bool,
+        /// Probability of each feedback tile being mistyped, weighs the candidates instead of dropping them
+        #[clap(long)]
+        noise: Option<f32>,
+        /// Continue the game saved with `save <file>`, later saves go to the same file
+        #[clap(long)]
+        resume: Option<PathBuf>,
+    },
+    /// How the word (the best opener by default) splits the dictionary into feedback buckets
+    Explain {
+        word: Option<String>,
+        /// Words shown for each bucket
+        #[clap(long, default_value = "8")]
+        samples: usize,
+    },
+    /// Possible answers of a pasted share grid (`🟩🟨⬛` rows on stdin)
+    Grid {
+        /// Any dictionary word can be the answer, not only the original answer list
+        #[clap(long)]
+        all_words: bool,
+        /// Known answer: list the words each row could have been guessed with instead
+        #[clap(long)]
+        answer: Option<String>,
+        /// Check the hard mode rules even without `*` in the header
+        #[clap(long)]
+        hard: bool,
+    },
+    /// Skill and luck of a played game, each guess next to the best one
+    Analyze {
+        /// Answer of the game
+        #[clap(long)]
+        answer: String,
+        /// Guesses in the order they were made
+        #[clap(required = true)]
+        guesses: Vec<String>,
+    },
+    /// Precomputed rankings for a fixed opener
+    #[clap(subcommand)]
+    Book(BookCommand),
+    /// Guess the secret word yourself, today's puzzle unless a seed or a date is given
+    Play {
+        /// Random answer picked by the seed instead of the daily one
+        #[clap(long, conflicts_with = "date")]
+        seed: Option<u64>,
+        /// Puzzle of the date (`YYYY-MM-DD`)
+        #[clap(long)]
+        date: Option<String>,
+        /// Stats and streak file, `~/.wordle-solver-stats.json` by default
+        #[clap(long)]
+        stats: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BookCommand {
+    /// Rank the next guesses for every feedback to the opener and save them
+    Build {
+        #[clap(long, default_value = "tares")]
+        opener: String,
+        /// Guesses covered: 2 ranks the second one, 3 follows the best second guess
+        #[clap(long, default_value = "2")]
+        depth: usize,
+        #[clap(long, short)]
+        output: PathBuf,
+    },
 }
 
 fn main() {
@@ -43,195 +194,409 @@ fn main() {
 
     let now = std::time::Instant::now();
 
-    let (all_bytes, all_words) = all_words();
-    let all_patterns = all_patterns(&all_bytes);
-
-    let infs: Vec<f32>;
-    match cli.implementation {
-        Implementation::CPU => {
-            infs = match_freq(&all_words, &all_patterns);
-        }
-        Implementation::SIMD => {
-            let all_simd_patterns = all_simd_patterns(&all_patterns);
-            infs = match_freq_simd(&all_words, &all_simd_patterns);
-        }
-        Implementation::GPU => {
-            infs = futures::executor::block_on(shader::match_freq(&all_words, &all_patterns))
-                .expect("Failed to compute shader");
+    let dictionary = Arc::new(Dictionary::bundled());
+    let backend = BackendKind::from(cli.implementation.clone())
+        .build()
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+    match cli.command {
+        Some(Command::Serve { addr }) => serve(&addr, dictionary, backend),
+        Some(Command::Rpc) => rpc(dictionary, backend),
+        Some(Command::Solve {
+            tui,
+            noise,
+            ref resume,
+        }) => solve(&cli, dictionary, backend, tui, noise, resume.clone()),
+        Some(Command::Explain { ref word, samples }) => {
+            explain(&cli, dictionary, backend, word.as_deref(), samples)
         }
+        Some(Command::Grid {
+            answer: Some(ref answer),
+            hard,
+            ..
+        }) => guesses(&cli, &dictionary, answer, hard),
+        Some(Command::Grid { all_words, .. }) => grid(&cli, &dictionary, all_words),
+        Some(Command::Analyze {
+            ref answer,
+            ref guesses,
+        }) => analyze_game(dictionary, backend, answer, guesses),
+        Some(Command::Book(BookCommand::Build {
+            ref opener,
+            depth,
+            ref output,
+        })) => build_book(&cli, dictionary, backend, opener, depth, output),
+        Some(Command::Play {
+            seed,
+            ref date,
+            ref stats,
+        }) => play(&dictionary, seed, date.as_deref(), stats.clone()),
+        None => rank(&cli, dictionary, backend, now),
     }
+}
 
-    let idx = top_k_indices::<10>(&infs);
-
-    println!("Top choices by information gain:");
-    for i in idx {
-        println!("{}: {}", wordbytes_to_str(&all_bytes[i]), infs[i]);
+/**
+    Solver with the strategy and the book from the command line
+**/
+fn solver(cli: &Cli, dictionary: Arc<Dictionary>, backend: Arc<dyn EntropyBackend>) -> Solver {
+    let solver = Solver::new(dictionary, backend)
+        .with_strategy(StrategyKind::from(cli.strategy.clone()).build());
+
+    let path = match &cli.book {
+        Some(path) => path,
+        None => return solver,
+    };
+    let book: Book = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read the book {}: {}", path.display(), err);
+            std::process::exit(1);
+        });
+    if let Err(err) = book.check(&solver) {
+        eprintln!("Not using {}: {}", path.display(), err);
+        return solver;
     }
 
-    let time = now.elapsed().as_millis();
-    println!("Time: {}ms", time);
+    solver.with_book(Arc::new(book))
 }
 
-fn all_words() -> (Vec<WordBytes>, Vec<Word>) {
-    include_str!("../dict.txt")
-        .lines()
-        .map(|s| {
-            let bytes = wordbytes_from_str(s);
-            (bytes, Word::from_wordbytes(&bytes))
-        })
-        .unzip()
-}
-
-fn all_patterns(words: &[WordBytes]) -> Vec<Patterns> {
-    let words = &words[0..WORD_COUNT];
+fn rank(
+    cli: &Cli,
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    now: std::time::Instant,
+) {
+    let solver = solver(cli, dictionary.clone(), backend);
+
+    let format = Format::from(cli.format.clone());
+    let n = if cli.all { dictionary.len() } else { cli.top };
+    let reports = solver.report(n);
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    let mut write = || -> std::io::Result<()> {
+        if format == Format::Text {
+            writeln!(
+                out,
+                "Top choices by {} ({}):",
+                solver.strategy().name(),
+                solver.backend().name()
+            )?;
+        }
 
-    words.iter().map(|word| Pattern::from_bytes(word)).collect()
-}
+        write_reports(&mut out, format, &reports)?;
 
-fn all_simd_patterns(patterns: &[Patterns]) -> Vec<SimdPatterns> {
-    let patterns = &patterns[0..WORD_COUNT];
+        if format == Format::Text {
+            let time = now.elapsed().as_millis();
+            writeln!(out, "Time: {}ms", time)?;
+        }
 
-    patterns.iter().map(SimdPattern::from_patterns).collect()
+        Ok(())
+    };
+    exit_on_output_error(write());
 }
 
-fn top_k_indices<const K: usize>(keys: &[f32]) -> [usize; K] {
-    let mut idx = [0; K];
-    for i in 1..keys.len() {
-        if keys[idx[0]] <= keys[i] {
-            idx.copy_within(0..K - 1, 1);
-            idx[0] = i;
+/**
+    The reader going away (`| head`) ends the command quietly, other errors
+    of the standard streams are fatal
+**/
+fn exit_on_output_error(result: std::io::Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(err) => {
+            eprintln!("Failed to talk over stdio: {}", err);
+            std::process::exit(1);
         }
     }
-
-    idx
 }
 
-fn counts_to_entropy(counts: &[usize]) -> f32 {
-    counts
-        .into_iter()
-        .filter(|&&f| f != 0)
-        .map(|&f| f as f32 / WORD_COUNT as f32)
-        .map(|p| -p * p.log2())
-        .sum()
-}
+fn rpc(dictionary: Arc<Dictionary>, backend: Arc<dyn EntropyBackend>) {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
 
-fn match_freq(words: &[Word], patterns: &[Patterns]) -> Vec<f32> {
-    patterns
-        .into_par_iter()
-        .map(|patterns| match_patterns(&words, patterns))
-        .map(|bins| counts_to_entropy(&bins))
-        .collect()
+    exit_on_output_error(
+        RpcServer::new(Api::new(dictionary, backend)).run(stdin.lock(), stdout.lock()),
+    );
 }
 
-fn match_patterns(words: &[Word], patterns: &Patterns) -> [usize; PATTERN_COUNT] {
-    let words = &words[0..WORD_COUNT];
-    let mut matches = [0; PATTERN_COUNT];
+fn solve(
+    cli: &Cli,
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    tui: bool,
+    noise: Option<f32>,
+    resume: Option<PathBuf>,
+) {
+    let mut solver = solver(cli, dictionary, backend);
+    if let Some(error) = noise {
+        solver = solver.with_noise(error);
+    }
+    if let Some(path) = &resume {
+        solver = Session::load(path)
+            .and_then(|session| session.restore(solver))
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to resume {}: {}", path.display(), err);
+                std::process::exit(1);
+            });
+    }
 
-    for (i, pattern) in patterns.into_iter().enumerate() {
-        for other in words {
-            if pattern.matches_word(&other) {
-                matches[i] += 1
-            }
-        }
+    if tui {
+        return solve_tui(solver, cli.top);
     }
 
-    matches
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+
+    let mut repl = Repl::new(solver, cli.top);
+    if let Some(path) = resume {
+        repl = repl.with_save_file(path);
+    }
+    exit_on_output_error(repl.run(stdin.lock(), stdout.lock()));
 }
 
-fn match_freq_simd(words: &[Word], patterns: &[SimdPatterns]) -> Vec<f32> {
-    patterns
-        .into_par_iter()
-        .map(|patterns| match_patterns_simd(&words, &patterns))
-        .map(|bins| counts_to_entropy(&bins))
-        .collect()
+fn explain(
+    cli: &Cli,
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    word: Option<&str>,
+    samples: usize,
+) {
+    let solver = solver(cli, dictionary, backend);
+    let word = match word {
+        Some(word) => word.to_lowercase(),
+        None => solver.report(1)[0].word.clone(),
+    };
+
+    let explanation = ExplainResponse::new(&solver, &word).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    exit_on_output_error(explanation.write_text(&mut std::io::stdout().lock(), samples));
 }
 
-fn match_patterns_simd(words: &[Word], patterns: &SimdPatterns) -> [usize; PATTERN_COUNT] {
-    let words = &words[0..WORD_COUNT];
-    let mut matches = [0; PATTERN_COUNT];
+fn read_grid() -> ShareGrid {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .expect("Failed to read stdin");
 
-    for (i, pattern) in patterns.into_iter().enumerate() {
-        let mut f = FreqSimd::splat(0);
-        for other in words {
-            let mask = pattern.matches_word(&other);
-            f -= mask.to_int();
-        }
-        let offset = i * SIMD_WIDTH;
-        let end_offset = (offset + SIMD_WIDTH).min(PATTERN_COUNT);
-        let slice_length = (PATTERN_COUNT - offset).min(SIMD_WIDTH);
-        matches[offset..end_offset]
-            .copy_from_slice(&f.to_array().map(|x| x.unsigned_abs() as usize)[..slice_length]);
-    }
-
-    matches
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::{all_patterns, all_simd_patterns, all_words, match_patterns, match_patterns_simd};
-    use pretty_assertions::assert_eq;
-    use std::collections::HashSet;
-
-    const KNOWN_WORD: &str = "sorel";
-    const KNOWN_BINS: [usize; 182] = [
-        986, 923, 366, 324, 193, 129, 375, 349, 54, 374, 256, 70, 200, 70, 22, 74, 54, 8, 189, 170,
-        57, 39, 17, 14, 77, 65, 13, 636, 403, 203, 131, 44, 28, 97, 41, 11, 388, 148, 41, 83, 18,
-        9, 16, 6, 1, 122, 66, 19, 12, 9, 3, 18, 7, 368, 369, 107, 19, 11, 5, 143, 96, 6, 311, 76,
-        36, 20, 3, 1, 69, 16, 3, 73, 43, 22, 4, 2, 11, 11, 5, 434, 326, 106, 123, 66, 31, 114, 119,
-        17, 54, 11, 4, 15, 2, 1, 13, 7, 1, 23, 30, 1, 2, 5, 2, 321, 158, 58, 63, 10, 4, 43, 7, 6,
-        39, 9, 5, 3, 11, 4, 1, 125, 94, 15, 6, 4, 1, 26, 24, 3, 57, 6, 4, 5, 1, 5, 2, 1, 5, 4, 1,
-        94, 6, 33, 21, 2, 10, 31, 2, 45, 3, 16, 1, 3, 11, 1, 3, 9, 1, 35, 1, 15, 3, 1, 2, 1, 8, 2,
-        5, 1, 2, 53, 4, 9, 1, 1, 15, 2, 13, 1, 1, 3, 4, 1,
-    ];
-
-    #[test]
-    fn known_bins_cpu() {
-        let (bytes, words) = all_words();
-        let patterns = all_patterns(&bytes);
-
-        let sorel_idx = words
-            .iter()
-            .enumerate()
-            .find(|(_, w)| w.to_str() == KNOWN_WORD)
-            .unwrap()
-            .0;
-        let sorel_pattens = patterns[sorel_idx];
+    text.parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
+
+fn grid(cli: &Cli, dictionary: &Dictionary, all_words: bool) {
+    let grid = read_grid();
+    let answers: Vec<usize> = if all_words {
+        (0..dictionary.len()).collect()
+    } else {
+        (0..BUNDLED_ANSWERS.min(dictionary.len())).collect()
+    };
+    let likely = infer_answers(dictionary, &answers, &grid.rows);
+
+    let mut title = grid
+        .puzzle
+        .map_or(String::new(), |p| format!("Wordle {} ", p));
+    if grid.hard_mode {
+        title.push_str("(hard mode) ");
+    }
+    println!(
+        "{}{} rows, {} possible answers",
+        title,
+        grid.rows.len(),
+        likely.len()
+    );
+
+    for l in likely.iter().take(cli.top) {
+        let per_row: Vec<String> = l.guesses_per_row.iter().map(|n| n.to_string()).collect();
+        println!(
+            "{}: {:.2}% ({} guesses per row)",
+            dictionary.word(l.answer),
+            l.probability * 100.0,
+            per_row.join("/")
+        );
+    }
+}
+
+fn guesses(cli: &Cli, dictionary: &Dictionary, answer: &str, hard: bool) {
+    let grid = read_grid();
+    let answer = dictionary.index_of(answer).unwrap_or_else(|| {
+        eprintln!("`{}` is not in the dictionary", answer);
+        std::process::exit(1);
+    });
+    let found = reconstruct(dictionary, answer, &grid.rows, hard || grid.hard_mode);
 
-        let bins: Vec<usize> = match_patterns(&words, &sorel_pattens)
+    for (i, (row, words)) in grid.rows.iter().zip(&found.guesses).enumerate() {
+        let sample: Vec<String> = words
             .iter()
-            .map(|x| x.to_owned())
-            .filter(|&x| x != 0)
+            .take(cli.top)
+            .map(|&w| dictionary.word(w))
             .collect();
+        let more = if words.len() > cli.top { " ..." } else { "" };
+        println!(
+            "{} {} {} words: {}{}",
+            i + 1,
+            row.to_emoji(),
+            words.len(),
+            sample.join(" "),
+            more
+        );
+    }
 
-        let bh: HashSet<usize> = HashSet::from_iter(bins);
-        let eh: HashSet<usize> = HashSet::from_iter(KNOWN_BINS);
+    for problem in &found.problems {
+        println!("Impossible: {}", problem);
+    }
+    println!("{} guess sequences", found.sequences);
+}
 
-        assert_eq!(bh, eh)
+fn analyze_game(
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    answer: &str,
+    guesses: &[String],
+) {
+    let index = |word: &str| {
+        dictionary
+            .index_of(&word.to_lowercase())
+            .unwrap_or_else(|| {
+                eprintln!("`{}` is not in the dictionary", word);
+                std::process::exit(1);
+            })
+    };
+    let answer = index(answer);
+    let guesses: Vec<usize> = guesses.iter().map(|g| index(g)).collect();
+
+    let solver = Solver::new(dictionary.clone(), backend);
+    let game = analyze(&solver, answer, &guesses);
+
+    for (i, g) in game.guesses.iter().enumerate() {
+        println!(
+            "{} {} {} {} -> {} candidates, expected {:.2} bits, got {:.2}, best {} {:.2} bits, skill {}, luck {}",
+            i + 1,
+            dictionary.word(g.guess),
+            g.feedback.to_emoji(),
+            g.candidates,
+            g.remaining,
+            g.expected_bits,
+            g.actual_bits,
+            dictionary.word(g.best),
+            g.best_bits,
+            g.skill,
+            g.luck
+        );
     }
+    println!("Skill {}, luck {}", game.skill, game.luck);
+}
 
-    #[test]
-    fn known_bins_simd() {
-        let (bytes, words) = all_words();
-        let patterns = all_patterns(&bytes);
-        let simd_patterns = all_simd_patterns(&patterns);
+fn build_book(
+    cli: &Cli,
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    opener: &str,
+    depth: usize,
+    output: &Path,
+) {
+    let opener = dictionary.index_of(opener).unwrap_or_else(|| {
+        eprintln!("`{}` is not in the dictionary", opener);
+        std::process::exit(1);
+    });
+    let solver = Solver::new(dictionary, backend)
+        .with_strategy(StrategyKind::from(cli.strategy.clone()).build());
+
+    let book = build(&solver, opener, depth, cli.top);
+    let json = serde_json::to_string(&book).unwrap();
+    std::fs::write(output, json).unwrap_or_else(|err| {
+        eprintln!("Failed to write {}: {}", output.display(), err);
+        std::process::exit(1);
+    });
+
+    println!("{} positions saved to {}", book.entries(), output.display());
+}
 
-        let sorel_idx = words
-            .iter()
-            .enumerate()
-            .find(|(_, w)| w.to_str() == KNOWN_WORD)
-            .unwrap()
-            .0;
-        let sorel_pattens = simd_patterns[sorel_idx];
+fn play(dictionary: &Dictionary, seed: Option<u64>, date: Option<&str>, stats: Option<PathBuf>) {
+    let puzzle = match (seed, date) {
+        (Some(_), _) => None,
+        (None, Some(date)) => Some(puzzle_of_date(date).unwrap_or_else(|| {
+            eprintln!(
+                "`{}` is not a date of a puzzle (`YYYY-MM-DD`, 2021-06-19 or later)",
+                date
+            );
+            std::process::exit(1);
+        })),
+        (None, None) => Some(puzzle_of_today()),
+    };
+    let answer = match (seed, puzzle) {
+        (Some(seed), _) => random_answer(seed),
+        (None, Some(puzzle)) => daily_answer(puzzle),
+        (None, None) => unreachable!(),
+    };
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let mut play = Play::new(dictionary, answer, puzzle);
+    exit_on_output_error(play.run(stdin.lock(), stdout.lock()));
+    if !play.is_over() {
+        return;
+    }
 
-        let bins: Vec<usize> = match_patterns_simd(&words, &sorel_pattens)
-            .iter()
-            .map(|x| x.to_owned())
-            .filter(|&x| x != 0)
-            .collect();
+    let path = stats.or_else(|| {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".wordle-solver-stats.json"))
+    });
+    if let Some(path) = path {
+        let mut stats = match Stats::load(&path) {
+            Ok(stats) => stats,
+            Err(err) => {
+                eprintln!(
+                    "Failed to read the stats from {}, left as they are: {}",
+                    path.display(),
+                    err
+                );
+                print!("{}", play.share());
+                return;
+            }
+        };
+        if stats.record(puzzle, play.game()) {
+            let json = serde_json::to_string_pretty(&stats).unwrap();
+            std::fs::write(&path, json).unwrap_or_else(|err| {
+                eprintln!("Failed to save the stats to {}: {}", path.display(), err)
+            });
+        } else {
+            println!("Puzzle {} is already counted in the stats", puzzle.unwrap());
+        }
+        println!("\n{}", stats);
+    }
 
-        let bh: HashSet<usize> = HashSet::from_iter(bins);
-        let eh: HashSet<usize> = HashSet::from_iter(KNOWN_BINS);
+    print!("{}", play.share());
+}
+
+#[cfg(feature = "tui")]
+fn solve_tui(solver: Solver, top: usize) {
+    wordle_solver::tui::run(solver, top).expect("Failed to draw the terminal UI");
+}
 
-        assert_eq!(bh, eh)
+#[cfg(not(feature = "tui"))]
+fn solve_tui(_solver: Solver, _top: usize) {
+    eprintln!("`solve --tui` requires the `tui` feature");
+    std::process::exit(1);
+}
+
+#[cfg(feature = "server")]
+fn serve(addr: &str, dictionary: Arc<Dictionary>, backend: Arc<dyn EntropyBackend>) {
+    eprintln!("Serving on http://{} ({})", addr, backend.name());
+    if let Err(err) = wordle_solver::server::serve(addr, Api::new(dictionary, backend)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
     }
 }
+
+#[cfg(not(feature = "server"))]
+fn serve(_addr: &str, _dictionary: Arc<Dictionary>, _backend: Arc<dyn EntropyBackend>) {
+    eprintln!("`serve` requires the `server` feature");
+    std::process::exit(1);
+}
@@ -1,6 +1,6 @@
 use crate::fivegram::FIVEGRAM;
 use crate::word::WordBytes;
-use crate::{AsciiBitSet, Fivegram, Word};
+use crate::{AsciiBitSet, Feedback, Fivegram, Tile, Word};
 use std::fmt::{Display, Formatter};
 
 pub const PATTERN_COUNT: usize = 3usize.pow(FIVEGRAM as u32);
@@ -26,11 +26,37 @@ pub struct Pattern {
     pub absent_letter: AsciiBitSet,
 }
 
+/**
+    Tiles of the pattern followed by the letters it knows the positions of
+    and the absent ones: `🟩🟨⬛⬛⬛ ta___ -ers`
+**/
 impl Display for Pattern {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f,
-               "Pattern {{ match_word: `{}`, present_letter: `{}`, absent_word: `{}`, absent_letter: `{}` }}", 
-               self.match_word, self.present_letter, self.absent_word, self.absent_letter)
+        let letter = |fivegram: &Fivegram, i: usize| {
+            let known = fivegram.letter_mask >> (i * FIVEGRAM) & 0b11111 != 0;
+            known.then(|| char::from((fivegram.word >> (i * FIVEGRAM) & 0b11111) as u8 - 1 + b'a'))
+        };
+
+        let mut tiles = [Tile::Grey; FIVEGRAM];
+        let mut letters = String::new();
+        for (i, tile) in tiles.iter_mut().enumerate() {
+            if let Some(l) = letter(&self.match_word, i) {
+                *tile = Tile::Green;
+                letters.push(l);
+            } else if let Some(l) = letter(&self.absent_word, i) {
+                *tile = Tile::Yellow;
+                letters.push(l);
+            } else {
+                letters.push('_');
+            }
+        }
+
+        write!(f, "{} {}", Feedback::from_tiles(tiles).to_emoji(), letters)?;
+        if self.absent_letter.set != 0 {
+            write!(f, " -{}", self.absent_letter)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -79,7 +105,8 @@ mod tests {
     use pretty_assertions::assert_eq;
     use std::collections::HashSet;
 
-    use crate::{AsciiBitSet, Pattern, Word};
+    use crate::word::wordbytes_from_str;
+    use crate::{AsciiBitSet, Feedback, Pattern, Word};
     use proptest::prelude::*;
 
     #[test]
@@ -130,6 +157,16 @@ mod tests {
         });
     }
 
+    #[test]
+    fn display_as_tiles() {
+        let patterns = Pattern::from_bytes(&wordbytes_from_str("tares"));
+        let pattern = |feedback: &str| patterns[feedback.parse::<Feedback>().unwrap().index()];
+
+        assert_eq!(pattern("GYBBB").to_string(), "🟩🟨⬛⬛⬛ ta___ -ers");
+        assert_eq!(pattern("GGGGG").to_string(), "🟩🟩🟩🟩🟩 tares");
+        assert_eq!(Pattern::default().to_string(), "⬛⬛⬛⬛⬛ _____");
+    }
+
     #[test]
     fn test_matching_empty_pattern() {
         let word = Word::from_str("hello");
@@ -0,0 +1,709 @@
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::api::{ApiError, ExplainResponse};
+use crate::consistency::{check_row, suggest_fix, Contradiction};
+use crate::feedback::ParseFeedbackError;
+use crate::report::{write_reports, Format};
+use crate::session::{Session, SessionError};
+use crate::solver::SolverError;
+use crate::{Feedback, Solver};
+
+const HELP: &str = "\
+<guess> <feedback>    apply the feedback, e.g. `tares BBGGY` (or the emoji)
+undo                  take back the last guess
+if [word] <feedback>  explore the feedback (to the best guess by default) without leaving the game
+pop                   back to where the last `if` branched off
+top [n]               best next guesses
+explain [word]        how the word (the best guess by default) splits the candidates
+candidates [n]        words still possible
+why <word>            the guess which ruled the word out and the constraint it broke
+save [file]           save the game for `solve --resume` (the resumed file by default)
+help                  this text
+quit                  leave";
+
+const EXPLAIN_SAMPLES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Guess {
+        word: String,
+        feedback: Feedback,
+    },
+    Undo,
+    If {
+        word: Option<String>,
+        feedback: Feedback,
+    },
+    Pop,
+    Top(Option<usize>),
+    Explain(Option<String>),
+    Candidates(Option<usize>),
+    Why(String),
+    Save(Option<PathBuf>),
+    Help,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    Unknown(String),
+    InvalidFeedback(ParseFeedbackError),
+    InvalidNumber(String),
+    Solver(SolverError),
+    /**
+        Feedback contradicting the earlier rows, with the single tile fix
+        (`<guess> <feedback>`) if there's one
+    **/
+    Contradiction {
+        contradiction: Contradiction,
+        fix: Option<String>,
+    },
+    NoCandidates,
+    NoSaveFile,
+    Session(SessionError),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Unknown(line) => write!(f, "unknown command `{}`, try `help`", line),
+            CommandError::InvalidFeedback(err) => write!(f, "{}", err),
+            CommandError::InvalidNumber(n) => write!(f, "`{}` is not a number", n),
+            CommandError::Solver(err) => write!(f, "{}", err),
+            CommandError::Contradiction { contradiction, fix } => {
+                write!(f, "{}", contradiction)?;
+                match fix {
+                    Some(fix) => write!(f, ", did you mean `{}`?", fix),
+                    None => write!(f, ", check the feedback"),
+                }
+            }
+            CommandError::NoCandidates => write!(f, "no candidates left"),
+            CommandError::NoSaveFile => write!(f, "no file to save to, `save <file>`"),
+            CommandError::Session(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<SolverError> for CommandError {
+    fn from(err: SolverError) -> Self {
+        CommandError::Solver(err)
+    }
+}
+
+fn count(arg: Option<&str>) -> Result<Option<usize>, CommandError> {
+    arg.map(|n| {
+        n.parse()
+            .map_err(|_| CommandError::InvalidNumber(n.to_string()))
+    })
+    .transpose()
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut args = line.split_whitespace();
+        let command = match args.next() {
+            Some(command) => command,
+            None => return Err(CommandError::Unknown(String::new())),
+        };
+        if command == "if" {
+            let args: Vec<&str> = args.collect();
+            let (word, feedback) = match args[..] {
+                [feedback] => (None, feedback),
+                [word, feedback] => (Some(word.to_lowercase()), feedback),
+                _ => return Err(CommandError::Unknown(line.trim().to_string())),
+            };
+
+            return Ok(Command::If {
+                word,
+                feedback: feedback.parse().map_err(CommandError::InvalidFeedback)?,
+            });
+        }
+        let arg = args.next();
+        if args.next().is_some() {
+            return Err(CommandError::Unknown(line.trim().to_string()));
+        }
+
+        match (command, arg) {
+            ("undo", None) => Ok(Command::Undo),
+            ("pop", None) => Ok(Command::Pop),
+            ("top", n) => Ok(Command::Top(count(n)?)),
+            ("explain" | "e", word) => Ok(Command::Explain(word.map(str::to_lowercase))),
+            ("candidates" | "c", n) => Ok(Command::Candidates(count(n)?)),
+            ("why", Some(word)) => Ok(Command::Why(word.to_lowercase())),
+            ("save", file) => Ok(Command::Save(file.map(PathBuf::from))),
+            ("help" | "?", None) => Ok(Command::Help),
+            ("quit" | "exit" | "q", None) => Ok(Command::Quit),
+            ("undo" | "pop" | "help" | "?" | "quit" | "exit" | "q", Some(_)) | ("why", None) => {
+                Err(CommandError::Unknown(line.trim().to_string()))
+            }
+            (word, Some(feedback)) => Ok(Command::Guess {
+                word: word.to_lowercase(),
+                feedback: feedback.parse().map_err(CommandError::InvalidFeedback)?,
+            }),
+            _ => Err(CommandError::Unknown(line.trim().to_string())),
+        }
+    }
+}
+
+/**
+    Line based `solve` session: guesses with their feedback in, remaining
+    candidates and the best next guesses out
+**/
+pub struct Repl {
+    solver: Solver,
+    top: usize,
+    save_file: Option<PathBuf>,
+    /**
+        Games the `if` branches left with the feedback they explore, the
+        innermost last
+    **/
+    branches: Vec<(Solver, usize, Feedback)>,
+}
+
+impl Repl {
+    pub fn new(solver: Solver, top: usize) -> Self {
+        Repl {
+            solver,
+            top,
+            save_file: None,
+            branches: vec![],
+        }
+    }
+
+    /**
+        File `save` writes to when it's not given one, the last one it saved
+        to is remembered the same way
+    **/
+    #[must_use]
+    pub fn with_save_file(mut self, path: PathBuf) -> Self {
+        self.save_file = Some(path);
+        self
+    }
+
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{} candidates, enter `<guess> <feedback>` or `help`",
+            self.solver.candidates().len()
+        )?;
+
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match line.parse() {
+                Ok(Command::Quit) => break,
+                Ok(command) => {
+                    // commands write to memory, the errors of `out` come back here
+                    let mut buffer = vec![];
+                    let result = self.execute(command, &mut buffer);
+                    out.write_all(&buffer)?;
+                    if let Err(err) = result {
+                        writeln!(out, "{}", err)?;
+                    }
+                }
+                Err(err) => writeln!(out, "{}", err)?,
+            }
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+        Runs the command, usage errors are returned for the caller to report
+    **/
+    pub fn execute(&mut self, command: Command, out: &mut impl Write) -> Result<(), CommandError> {
+        match command {
+            Command::Guess { word, feedback } => {
+                let guess = self
+                    .solver
+                    .dictionary()
+                    .index_of(&word)
+                    .ok_or_else(|| SolverError::UnknownWord(word.clone()))?;
+                self.check(guess, feedback)?;
+
+                self.solver.apply_index(guess, feedback);
+                if feedback.is_solved() {
+                    writeln!(out, "Solved in {}", self.solver.history().len()).unwrap();
+                } else if self.solver.candidates().is_empty() {
+                    // consistent but no word fits, the fix is looked up from before the guess
+                    self.solver.undo();
+                    let fix = suggest_fix(&self.solver, guess, feedback);
+                    self.solver.apply_index(guess, feedback);
+
+                    write!(out, "No dictionary word fits").unwrap();
+                    match fix {
+                        Some(fix) => writeln!(
+                            out,
+                            ", did you mean `{} {}`? `undo` takes it back",
+                            word, fix
+                        ),
+                        None => writeln!(out, ", check the feedback or `undo`"),
+                    }
+                    .unwrap();
+                } else {
+                    self.status(out);
+                }
+            }
+            Command::Undo => match self.solver.undo() {
+                Some((guess, feedback)) => {
+                    let word = self.solver.dictionary().word(guess);
+                    writeln!(out, "Undid {} {}", word, feedback).unwrap();
+                    self.status(out);
+                }
+                None => writeln!(out, "Nothing to undo").unwrap(),
+            },
+            Command::If { word, feedback } => {
+                let guess = match word {
+                    Some(word) => self
+                        .solver
+                        .dictionary()
+                        .index_of(&word)
+                        .ok_or(SolverError::UnknownWord(word))?,
+                    None if self.solver.candidates().is_empty() => {
+                        return Err(CommandError::NoCandidates)
+                    }
+                    None => self.solver.top(1)[0].0,
+                };
+                self.check(guess, feedback)?;
+
+                self.branches.push((self.solver.clone(), guess, feedback));
+                self.solver.apply_index(guess, feedback);
+                let word = self.solver.dictionary().word(guess);
+                writeln!(out, "Branch {}: {} {}", self.branches.len(), word, feedback).unwrap();
+                self.status(out);
+            }
+            Command::Pop => match self.branches.pop() {
+                Some((solver, guess, feedback)) => {
+                    let word = solver.dictionary().word(guess);
+                    writeln!(
+                        out,
+                        "Back from branch {}: {} {}",
+                        self.branches.len() + 1,
+                        word,
+                        feedback
+                    )
+                    .unwrap();
+                    self.solver = solver;
+                    self.status(out);
+                }
+                None => writeln!(out, "No branch to leave").unwrap(),
+            },
+            Command::Top(n) => {
+                let reports = self.solver.report(n.unwrap_or(self.top));
+                write_reports(out, Format::Text, &reports).unwrap();
+            }
+            Command::Explain(word) => {
+                if self.solver.candidates().is_empty() {
+                    return Err(CommandError::NoCandidates);
+                }
+                let word = match word {
+                    Some(word) => word,
+                    None => self.solver.report(1)[0].word.clone(),
+                };
+                let explanation =
+                    ExplainResponse::new(&self.solver, &word).map_err(|err| match err {
+                        ApiError::Solver(err) => CommandError::Solver(err),
+                        ApiError::InvalidWord(word) => {
+                            CommandError::Solver(SolverError::UnknownWord(word))
+                        }
+                    })?;
+                explanation.write_text(out, EXPLAIN_SAMPLES).unwrap();
+            }
+            Command::Candidates(n) if self.solver.noise().is_some() => {
+                let words: Vec<String> = self
+                    .solver
+                    .likely_candidates()
+                    .iter()
+                    .take(n.unwrap_or(usize::MAX))
+                    .map(|&(c, p)| {
+                        format!("{} {:.1}%", self.solver.dictionary().word(c), p * 100.0)
+                    })
+                    .collect();
+                writeln!(out, "{}", words.join(", ")).unwrap();
+            }
+            Command::Candidates(n) => {
+                let candidates = self.solver.candidates();
+                let words: Vec<String> = candidates
+                    .iter()
+                    .take(n.unwrap_or(usize::MAX))
+                    .map(|&c| self.solver.dictionary().word(c))
+                    .collect();
+                writeln!(out, "{}", words.join(" ")).unwrap();
+            }
+            Command::Why(word) => {
+                let dictionary = self.solver.dictionary();
+                let idx = dictionary
+                    .index_of(&word)
+                    .ok_or_else(|| SolverError::UnknownWord(word.clone()))?;
+                let bytes = dictionary.bytes();
+
+                let ruled_out = self.solver.history().iter().find_map(|&(guess, feedback)| {
+                    feedback
+                        .check(&bytes[guess], &bytes[idx])
+                        .err()
+                        .map(|violation| (guess, feedback, violation))
+                });
+                match ruled_out {
+                    Some((guess, feedback, violation)) => writeln!(
+                        out,
+                        "`{}` is ruled out by {} {}: {}",
+                        word,
+                        dictionary.word(guess),
+                        feedback,
+                        violation
+                    )
+                    .unwrap(),
+                    None => writeln!(out, "`{}` is still a candidate", word).unwrap(),
+                }
+            }
+            Command::Save(file) => {
+                let file = file
+                    .or_else(|| self.save_file.clone())
+                    .ok_or(CommandError::NoSaveFile)?;
+                let game = self.game();
+                Session::of(game)
+                    .and_then(|session| session.save(&file))
+                    .map_err(CommandError::Session)?;
+                write!(
+                    out,
+                    "Saved {} guesses to {}",
+                    game.history().len(),
+                    file.display()
+                )
+                .unwrap();
+                if !self.branches.is_empty() {
+                    write!(out, ", without the `if` branches").unwrap();
+                }
+                writeln!(out).unwrap();
+                self.save_file = Some(file);
+            }
+            Command::Help => writeln!(out, "{}", HELP).unwrap(),
+            Command::Quit => {}
+        }
+
+        Ok(())
+    }
+
+    /**
+        Rejects the feedback contradicting the game so far, or only itself
+        when the noisy solver weighs the rows
+    **/
+    fn check(&self, guess: usize, feedback: Feedback) -> Result<(), CommandError> {
+        match check_row(&self.solver, guess, feedback) {
+            Some(contradiction) => Err(CommandError::Contradiction {
+                contradiction,
+                fix: suggest_fix(&self.solver, guess, feedback)
+                    .map(|fix| format!("{} {}", self.solver.dictionary().word(guess), fix)),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /**
+        Game actually played, outside of the `if` branches
+    **/
+    fn game(&self) -> &Solver {
+        self.branches
+            .first()
+            .map_or(&self.solver, |(solver, _, _)| solver)
+    }
+
+    fn status(&self, out: &mut impl Write) {
+        match self.solver.candidates() {
+            [] => writeln!(out, "No candidates left, check the feedback or `undo`").unwrap(),
+            [answer] => {
+                writeln!(out, "Answer: {}", self.solver.dictionary().word(*answer)).unwrap()
+            }
+            candidates => {
+                write!(out, "{} candidates left", candidates.len()).unwrap();
+                if self.solver.noise().is_some() {
+                    let (likely, p) = self.solver.likely_candidates()[0];
+                    let word = self.solver.dictionary().word(likely);
+                    write!(out, ", most likely {} ({:.1}%)", word, p * 100.0).unwrap();
+                }
+                writeln!(out).unwrap();
+                let reports = self.solver.report(self.top);
+                write_reports(out, Format::Text, &reports).unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::backend::CpuBackend;
+    use crate::repl::{Command, CommandError, Repl};
+    use crate::session::Session;
+    use crate::{Dictionary, Feedback, Solver};
+
+    fn repl() -> Repl {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Repl::new(Solver::new(Arc::new(dictionary), Arc::new(CpuBackend)), 2)
+    }
+
+    fn run(repl: &mut Repl, input: &str) -> String {
+        let mut out = vec![];
+        repl.run(input.as_bytes(), &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            "tares BBGGY".parse(),
+            Ok(Command::Guess {
+                word: "tares".to_string(),
+                feedback: "BBGGY".parse::<Feedback>().unwrap()
+            })
+        );
+        assert_eq!("top".parse(), Ok(Command::Top(None)));
+        assert_eq!("c 3".parse(), Ok(Command::Candidates(Some(3))));
+        assert_eq!("explain".parse(), Ok(Command::Explain(None)));
+        assert_eq!("why Sorel".parse(), Ok(Command::Why("sorel".to_string())));
+        assert_eq!(
+            "if Tares BBGGY".parse(),
+            Ok(Command::If {
+                word: Some("tares".to_string()),
+                feedback: "BBGGY".parse::<Feedback>().unwrap()
+            })
+        );
+        assert!(matches!(
+            "if".parse::<Command>(),
+            Err(CommandError::Unknown(_))
+        ));
+        assert_eq!("pop".parse(), Ok(Command::Pop));
+        assert!(matches!(
+            "why".parse::<Command>(),
+            Err(CommandError::Unknown(_))
+        ));
+        assert_eq!(
+            "e Tares".parse(),
+            Ok(Command::Explain(Some("tares".to_string())))
+        );
+        assert_eq!(
+            "top x".parse::<Command>(),
+            Err(CommandError::InvalidNumber("x".to_string()))
+        );
+        assert!(matches!(
+            "tares BBQGY".parse::<Command>(),
+            Err(CommandError::InvalidFeedback(_))
+        ));
+        assert!(matches!(
+            "undo now".parse::<Command>(),
+            Err(CommandError::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn session() {
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "cigar BBBBB\ncandidates\nundo\nzzzzz BBBBB\nquit\ntop\n",
+        );
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "7 candidates, enter `<guess> <feedback>` or `help`",
+                "Answer: humph",
+                "humph",
+                "Undid cigar BBBBB",
+                "7 candidates left",
+                lines[5],
+                lines[6],
+                "`zzzzz` is not in the dictionary",
+            ]
+        );
+        assert_eq!(repl.solver().candidates().len(), 7);
+    }
+
+    #[test]
+    fn why() {
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "cigar BBBBY\ntares BBGGY\nwhy sorel\nwhy rebut\nwhy awake\nwhy zzzzz\n",
+        );
+        let lines: Vec<&str> = out.lines().rev().take(4).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "`zzzzz` is not in the dictionary",
+                "`awake` is ruled out by cigar BBBBY: can't have the letter `a`",
+                "`rebut` is ruled out by tares BBGGY: needs `r` at position 3",
+                "`sorel` is still a candidate",
+            ]
+        );
+    }
+
+    #[test]
+    fn noisy_row_contradicting_itself() {
+        let dictionary = Dictionary::parse("llama\nworld\nsorel\ntares").unwrap();
+        let solver = Solver::new(Arc::new(dictionary), Arc::new(CpuBackend)).with_noise(0.05);
+        let mut repl = Repl::new(solver, 2);
+        let out = run(&mut repl, "llama BYBBB\nwhy world\n");
+        let lines: Vec<&str> = out.lines().collect();
+
+        // noise doesn't let a row contradicting itself in
+        assert_eq!(lines[1..], vec![
+                "row 1: yellow `l` after a grey one, the first ones are coloured, did you mean `llama BGBBB`?",
+                "`world` is still a candidate",
+            ][..]);
+        assert!(repl.solver().history().is_empty());
+    }
+
+    #[test]
+    fn explain() {
+        let mut repl = repl();
+        let out = run(&mut repl, "explain cigar\nexplain zzzzz\nexplain\n");
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[1], "cigar: 2.52 bits, 6 buckets over 7 candidates");
+        assert_eq!(lines[8], "`zzzzz` is not in the dictionary");
+        // the best guess tells all of them apart
+        assert!(lines[9].ends_with("bits, 7 buckets over 7 candidates"));
+        assert_eq!(lines.len(), 17);
+    }
+
+    #[test]
+    fn save_and_resume() {
+        let file = std::env::temp_dir().join(format!("wordle-session-{}.json", std::process::id()));
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "save\ncigar BBBBY\nsave FILE\nsave\n"
+                .replace("FILE", file.to_str().unwrap())
+                .as_str(),
+        );
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[1], "no file to save to, `save <file>`");
+        assert_eq!(
+            lines[lines.len() - 1],
+            format!("Saved 1 guesses to {}", file.display())
+        );
+
+        let session = Session::load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        let resumed = session.restore(self::repl().solver).unwrap();
+        assert_eq!(resumed.candidates(), repl.solver().candidates());
+    }
+
+    #[test]
+    fn save_in_branch() {
+        let file = std::env::temp_dir().join(format!("wordle-branch-{}.json", std::process::id()));
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "cigar BBBBY\nif humph BBBBB\nsave FILE\n"
+                .replace("FILE", file.to_str().unwrap())
+                .as_str(),
+        );
+
+        assert_eq!(
+            out.lines().last().unwrap(),
+            format!(
+                "Saved 1 guesses to {}, without the `if` branches",
+                file.display()
+            )
+        );
+        let session = Session::load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(session.history.len(), 1);
+        assert_eq!(session.history[0].guess, "cigar");
+    }
+
+    #[test]
+    fn branches() {
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "if cigar BBBBY\nif rebut BBBBB\nif BBBBB\npop\npop\npop\n",
+        );
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "7 candidates, enter `<guess> <feedback>` or `help`",
+                "Branch 1: cigar BBBBY",
+                "2 candidates left",
+                "humph: 1",
+                "rebut: 1",
+                "row 1 needs at least 1 `r`, row 2 allows at most 0, check the feedback",
+                // the best guess tells rebut and sorel apart
+                "Branch 2: humph BBBBB",
+                "Answer: sorel",
+                "Back from branch 2: humph BBBBB",
+                "2 candidates left",
+                "humph: 1",
+                "rebut: 1",
+                "Back from branch 1: cigar BBBBY",
+                "7 candidates left",
+                "rebut: 2.8073552",
+                "sorel: 2.8073552",
+                "No branch to leave",
+            ]
+        );
+        assert!(repl.solver().history().is_empty());
+    }
+
+    #[test]
+    fn noisy() {
+        let mut repl = repl();
+        repl.solver = repl.solver.with_noise(0.05);
+        let out = run(&mut repl, "cigar BBBBB\ntares BBGGY\ncandidates 2\n");
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[1], "6 candidates left, most likely humph (90.4%)");
+        // tares outvotes the mistyped `r` of cigar
+        assert_eq!(lines[4], "3 candidates left, most likely sorel (99.9%)");
+        assert_eq!(lines[7], "sorel 99.9%, sissy 0.1%");
+    }
+
+    #[test]
+    fn contradictions() {
+        let mut repl = repl();
+        let out = run(
+            &mut repl,
+            "tares BBGGY\nsorel GGGYG\nsorel BGGGG\nhumph GBBBB\nundo\n",
+        );
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(
+            lines[2..],
+            vec![
+                "row 2: `e` at position 4 contradicts row 1, did you mean `sorel GGGGG`?",
+                "row 1 needs at least 1 `s`, row 2 allows at most 0, did you mean `sorel GGGGG`?",
+                "No dictionary word fits, did you mean `humph BBBBB`? `undo` takes it back",
+                "Undid humph GBBBB",
+                "Answer: sorel",
+            ][..]
+        );
+        assert_eq!(repl.solver().history().len(), 1);
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{histogram_to_entropy, Histogram};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+/**
+    Everything known about a guess: strategy score and its rank along with
+    the bucket metrics all of the strategies are built from
+**/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuessReport {
+    pub rank: usize,
+    pub word: String,
+    pub score: f32,
+    pub entropy: f32,
+    pub buckets: usize,
+    pub largest_bucket: f32,
+    pub expected_remaining: f32,
+    pub possible_answer: bool,
+}
+
+impl GuessReport {
+    pub fn new(
+        rank: usize,
+        word: String,
+        score: f32,
+        bins: &Histogram,
+        total: f32,
+        possible_answer: bool,
+    ) -> Self {
+        GuessReport {
+            rank,
+            word,
+            score,
+            entropy: histogram_to_entropy(bins, total),
+            buckets: bins.iter().filter(|&&n| n != 0.0).count(),
+            largest_bucket: bins.iter().copied().fold(0.0, f32::max),
+            expected_remaining: bins.iter().map(|&n| n * n).sum::<f32>() / total,
+            possible_answer,
+        }
+    }
+}
+
+const CSV_HEADER: &str =
+    "rank,word,score,entropy,buckets,largest_bucket,expected_remaining,possible_answer";
+
+pub fn write_reports(
+    out: &mut impl Write,
+    format: Format,
+    reports: &[GuessReport],
+) -> std::io::Result<()> {
+    match format {
+        Format::Text => {
+            for r in reports {
+                writeln!(out, "{}: {}", r.word, r.score)?;
+            }
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, reports)?;
+            writeln!(out)?;
+        }
+        Format::Ndjson => {
+            for r in reports {
+                serde_json::to_writer(&mut *out, r)?;
+                writeln!(out)?;
+            }
+        }
+        Format::Csv => {
+            writeln!(out, "{}", CSV_HEADER)?;
+            for r in reports {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    r.rank,
+                    r.word,
+                    r.score,
+                    r.entropy,
+                    r.buckets,
+                    r.largest_bucket,
+                    r.expected_remaining,
+                    r.possible_answer
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::pattern::PATTERN_COUNT;
+    use crate::report::{write_reports, Format, GuessReport};
+
+    fn reports() -> Vec<GuessReport> {
+        let mut bins = [0.0; PATTERN_COUNT];
+        bins[0] = 1.0;
+        bins[242] = 3.0;
+
+        vec![
+            GuessReport::new(1, "sorel".to_string(), 0.5, &bins, 4.0, true),
+            GuessReport::new(2, "tares".to_string(), 0.25, &bins, 4.0, false),
+        ]
+    }
+
+    fn write(format: Format) -> String {
+        let mut out = vec![];
+        write_reports(&mut out, format, &reports()).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn metrics() {
+        let report = &reports()[0];
+
+        assert_eq!(report.buckets, 2);
+        assert_eq!(report.largest_bucket, 3.0);
+        assert_eq!(report.expected_remaining, 2.5);
+        assert!((report.entropy - 0.8112781).abs() < 1e-6);
+    }
+
+    #[test]
+    fn csv() {
+        let csv = write(Format::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        assert!(lines[2].starts_with("2,tares,0.25,"));
+        assert!(lines[2].ends_with(",2,3,2.5,false"));
+    }
+
+    #[test]
+    fn ndjson_and_json() {
+        let ndjson = write(Format::Ndjson);
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let json: serde_json::Value = serde_json::from_str(&write(Format::Json)).unwrap();
+
+        assert_eq!(lines[0]["word"], "sorel");
+        assert_eq!(lines[1]["possible_answer"], false);
+        assert_eq!(json, serde_json::Value::Array(lines));
+    }
+}
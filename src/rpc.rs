@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::api::{Api, ApiError, ExplainResponse, SuggestResponse};
+use crate::solver::SolverError;
+use crate::{Feedback, Solver, StrategyKind};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const UNKNOWN_SESSION: i64 = -32001;
+const UNKNOWN_WORD: i64 = -32002;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        RpcError {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<SolverError> for RpcError {
+    fn from(err: SolverError) -> Self {
+        RpcError::new(UNKNOWN_WORD, err)
+    }
+}
+
+impl From<ApiError> for RpcError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::Solver(err) => err.into(),
+            err => RpcError::new(INVALID_PARAMS, err),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct NewGameParams {
+    #[serde(default)]
+    strategy: Option<StrategyKind>,
+}
+
+#[derive(Deserialize)]
+struct SessionParams {
+    session: u64,
+}
+
+#[derive(Deserialize)]
+struct FeedbackParams {
+    session: u64,
+    guess: String,
+    feedback: Feedback,
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    session: u64,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ExplainParams {
+    session: u64,
+    guess: String,
+}
+
+/**
+    Line-delimited JSON-RPC 2.0, one request per line in, one response per
+    line out. Games live in the sessions until `end_game`:
+
+    `new_game {strategy?}` -> `{session, candidates}`
+    `submit_feedback {session, guess, feedback}` -> `{candidates, solved}`
+    `suggest {session, limit?}` -> ranked guesses, same as `POST /suggest`
+    `candidates {session, limit?}` -> `{count, words}`
+    `explain {session, guess}` -> `{guess, entropy, buckets}`
+    `undo {session}`, `end_game {session}`
+**/
+pub struct RpcServer {
+    api: Api,
+    sessions: HashMap<u64, Solver>,
+    next_session: u64,
+}
+
+impl RpcServer {
+    pub fn new(api: Api) -> Self {
+        RpcServer {
+            api,
+            sessions: HashMap::new(),
+            next_session: 1,
+        }
+    }
+
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+        Response to the request, `None` for notifications (requests without an `id`)
+    **/
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => return Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, err)))),
+        };
+
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(err) => {
+                return Some(response(
+                    Value::Null,
+                    Err(RpcError::new(INVALID_REQUEST, err)),
+                ))
+            }
+        };
+
+        let result = self.call(&request.method, request.params);
+
+        (!request.id.is_null()).then(|| response(request.id, result))
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "new_game" => {
+                let params: NewGameParams = parse_params(params)?;
+                let solver = self
+                    .api
+                    .solver(params.strategy.unwrap_or(StrategyKind::Entropy));
+                let session = self.next_session;
+                self.next_session += 1;
+
+                let candidates = solver.candidates().len();
+                self.sessions.insert(session, solver);
+
+                Ok(json!({ "session": session, "candidates": candidates }))
+            }
+            "submit_feedback" => {
+                let params: FeedbackParams = parse_params(params)?;
+                let solver = self.session(params.session)?;
+                solver.apply(&params.guess, params.feedback)?;
+
+                Ok(json!({
+                    "candidates": solver.candidates().len(),
+                    "solved": params.feedback.is_solved(),
+                }))
+            }
+            "suggest" => {
+                let params: ListParams = parse_params(params)?;
+                let solver = self.session(params.session)?;
+
+                let response = SuggestResponse::new(solver, params.limit.unwrap_or(10));
+
+                Ok(serde_json::to_value(response).unwrap())
+            }
+            "candidates" => {
+                let params: ListParams = parse_params(params)?;
+                let solver = self.session(params.session)?;
+                let words: Vec<String> = solver
+                    .candidates()
+                    .iter()
+                    .take(params.limit.unwrap_or(usize::MAX))
+                    .map(|&c| solver.dictionary().word(c))
+                    .collect();
+
+                Ok(json!({ "count": solver.candidates().len(), "words": words }))
+            }
+            "explain" => {
+                let params: ExplainParams = parse_params(params)?;
+                let solver = self.session(params.session)?;
+                let response = ExplainResponse::new(solver, &params.guess)?;
+
+                Ok(serde_json::to_value(response).unwrap())
+            }
+            "undo" => {
+                let params: SessionParams = parse_params(params)?;
+                let solver = self.session(params.session)?;
+                let undone = solver.undo().map(|(guess, feedback)| {
+                    json!({ "guess": solver.dictionary().word(guess), "feedback": feedback })
+                });
+
+                Ok(json!({ "undone": undone, "candidates": solver.candidates().len() }))
+            }
+            "end_game" => {
+                let params: SessionParams = parse_params(params)?;
+                self.session(params.session)?;
+                self.sessions.remove(&params.session);
+
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method `{}`", method),
+            )),
+        }
+    }
+
+    fn session(&mut self, session: u64) -> Result<&mut Solver, RpcError> {
+        self.sessions
+            .get_mut(&session)
+            .ok_or_else(|| RpcError::new(UNKNOWN_SESSION, format!("unknown session {}", session)))
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err))
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": err.code, "message": err.message },
+        }),
+    };
+
+    response.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    use crate::api::Api;
+    use crate::backend::CpuBackend;
+    use crate::rpc::RpcServer;
+    use crate::Dictionary;
+
+    fn server() -> RpcServer {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        RpcServer::new(Api::new(Arc::new(dictionary), Arc::new(CpuBackend)))
+    }
+
+    fn call(server: &mut RpcServer, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = server.handle_line(&request.to_string()).unwrap();
+
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn game_session() {
+        let mut server = server();
+
+        let game = call(&mut server, "new_game", json!({}));
+        assert_eq!(game["result"], json!({ "session": 1, "candidates": 7 }));
+
+        let submitted = call(
+            &mut server,
+            "submit_feedback",
+            json!({ "session": 1, "guess": "cigar", "feedback": "BBBBB" }),
+        );
+        assert_eq!(
+            submitted["result"],
+            json!({ "candidates": 1, "solved": false })
+        );
+
+        let candidates = call(&mut server, "candidates", json!({ "session": 1 }));
+        assert_eq!(
+            candidates["result"],
+            json!({ "count": 1, "words": ["humph"] })
+        );
+
+        let suggest = call(&mut server, "suggest", json!({ "session": 1, "limit": 2 }));
+        assert_eq!(
+            suggest["result"]["suggestions"].as_array().unwrap().len(),
+            2
+        );
+
+        let explain = call(
+            &mut server,
+            "explain",
+            json!({ "session": 1, "guess": "humph" }),
+        );
+        assert_eq!(explain["result"]["entropy"], json!(0.0));
+        assert_eq!(explain["result"]["buckets"][0]["feedback"], "GGGGG");
+
+        let undo = call(&mut server, "undo", json!({ "session": 1 }));
+        assert_eq!(undo["result"]["candidates"], 7);
+
+        call(&mut server, "end_game", json!({ "session": 1 }));
+        let ended = call(&mut server, "candidates", json!({ "session": 1 }));
+        assert_eq!(ended["error"]["code"], -32001);
+    }
+
+    #[test]
+    fn errors() {
+        let mut server = server();
+
+        let parse: Value = serde_json::from_str(&server.handle_line("{").unwrap()).unwrap();
+        assert_eq!(parse["error"]["code"], -32700);
+        assert_eq!(
+            call(&mut server, "nope", Value::Null)["error"]["code"],
+            -32601
+        );
+        assert_eq!(
+            call(&mut server, "suggest", json!({}))["error"]["code"],
+            -32602
+        );
+
+        call(&mut server, "new_game", Value::Null);
+        let unknown = call(
+            &mut server,
+            "submit_feedback",
+            json!({ "session": 1, "guess": "zzzzz", "feedback": "BBBBB" }),
+        );
+        assert_eq!(unknown["error"]["code"], -32002);
+
+        let notification = r#"{"jsonrpc": "2.0", "method": "new_game"}"#;
+        assert_eq!(server.handle_line(notification), None);
+    }
+}
@@ -0,0 +1,127 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::api::{Api, ApiError};
+
+/**
+    Localhost JSON API:
+
+    `POST /suggest` - `{"history": [{"guess", "feedback"}], "strategy", "top"}` to ranked guesses
+    `POST /score` - `{"guess", "answer"}` to the feedback
+
+    Requests are handled on the rayon pool, all of them share the same
+    dictionary and backend (so the GPU device is only set up once).
+**/
+pub fn serve(addr: &str, api: Api) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let server = Server::http(addr)?;
+
+    for request in server.incoming_requests() {
+        let api = api.clone();
+        rayon::spawn(move || handle(&api, request));
+    }
+
+    Ok(())
+}
+
+fn handle(api: &Api, mut request: Request) {
+    let mut body = String::new();
+    let (status, json) = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => route(api, request.method(), request.url(), &body),
+        Err(err) => error(400, &err),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(json)
+        .with_status_code(status)
+        .with_header(header);
+
+    // client might be gone already, nothing to do about it
+    let _ = request.respond(response);
+}
+
+/**
+    Status code and JSON body of the response
+**/
+pub fn route(api: &Api, method: &Method, url: &str, body: &str) -> (u16, String) {
+    match (method, url) {
+        (Method::Post, "/suggest") => call(body, |req| api.suggest(req)),
+        (Method::Post, "/score") => call(body, |req| api.score(req)),
+        (_, "/suggest" | "/score") => error(405, &"method not allowed"),
+        _ => error(404, &"not found"),
+    }
+}
+
+fn call<Req, Resp>(body: &str, f: impl FnOnce(&Req) -> Result<Resp, ApiError>) -> (u16, String)
+where
+    Req: DeserializeOwned,
+    Resp: Serialize,
+{
+    let request = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => return error(400, &err),
+    };
+
+    match f(&request) {
+        Ok(response) => (200, serde_json::to_string(&response).unwrap()),
+        Err(err) => error(422, &err),
+    }
+}
+
+fn error(status: u16, err: &dyn std::fmt::Display) -> (u16, String) {
+    (
+        status,
+        serde_json::json!({ "error": err.to_string() }).to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tiny_http::Method;
+
+    use crate::api::Api;
+    use crate::backend::CpuBackend;
+    use crate::server::route;
+    use crate::Dictionary;
+
+    fn api() -> Api {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Api::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    #[test]
+    fn routes() {
+        let api = api();
+
+        let (status, body) = route(
+            &api,
+            &Method::Post,
+            "/score",
+            r#"{"guess": "tares", "answer": "sorel"}"#,
+        );
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""feedback":"BBGGY""#));
+
+        let (status, body) = route(&api, &Method::Post, "/suggest", r#"{"top": 3}"#);
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""candidates":7"#));
+
+        assert_eq!(route(&api, &Method::Get, "/suggest", "").0, 405);
+        assert_eq!(route(&api, &Method::Post, "/nope", "").0, 404);
+        assert_eq!(route(&api, &Method::Post, "/score", "{").0, 400);
+        assert_eq!(
+            route(
+                &api,
+                &Method::Post,
+                "/score",
+                r#"{"guess": "x", "answer": "sorel"}"#
+            )
+            .0,
+            422
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use serde::de::value::{Error as DeError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Serialize};
+
+use crate::consistency::{check_row, Contradiction};
+use crate::{Feedback, Solver, StrategyKind};
+
+/**
+    Interactive game saved to resume it later: the candidates are rebuilt
+    from the history, so the file only keeps what the user entered.
+**/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /**
+        `Dictionary::fingerprint` of the dictionary it was played with
+    **/
+    pub dictionary: u64,
+    pub strategy: StrategyKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<f32>,
+    pub history: Vec<Row>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Row {
+    pub guess: String,
+    pub feedback: Feedback,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    Io(String),
+    Format(String),
+    Dictionary,
+    Strategy(String),
+    UnknownWord(String),
+    Contradiction(Contradiction),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Io(err) => write!(f, "{}", err),
+            SessionError::Format(err) => write!(f, "not a saved session: {}", err),
+            SessionError::Dictionary => write!(f, "the session was played with another dictionary"),
+            SessionError::Strategy(name) => write!(f, "unknown strategy `{}`", name),
+            SessionError::UnknownWord(word) => write!(f, "`{}` is not in the dictionary", word),
+            SessionError::Contradiction(contradiction) => {
+                write!(
+                    f,
+                    "the saved feedback contradicts itself, {}",
+                    contradiction
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl Session {
+    /**
+        Game of the solver, its strategy has to be one of the `StrategyKind`s
+    **/
+    pub fn of(solver: &Solver) -> Result<Self, SessionError> {
+        let dictionary = solver.dictionary();
+        let name = solver.strategy().name();
+        let kind: StrDeserializer<DeError> = name.into_deserializer();
+        let strategy = StrategyKind::deserialize(kind)
+            .map_err(|_| SessionError::Strategy(name.to_string()))?;
+
+        Ok(Session {
+            dictionary: dictionary.fingerprint(),
+            strategy,
+            noise: solver.noise(),
+            history: solver
+                .history()
+                .iter()
+                .map(|&(guess, feedback)| Row {
+                    guess: dictionary.word(guess),
+                    feedback,
+                })
+                .collect(),
+        })
+    }
+
+    /**
+        Replays the history on a fresh solver of the same dictionary, with the
+        strategy and the noise the session was saved with. Rows are checked
+        the way the game checks them when entered.
+    **/
+    pub fn restore(&self, solver: Solver) -> Result<Solver, SessionError> {
+        if self.dictionary != solver.dictionary().fingerprint() {
+            return Err(SessionError::Dictionary);
+        }
+        let mut solver = solver.with_strategy(self.strategy.build());
+        if let Some(error) = self.noise {
+            solver = solver.with_noise(error);
+        }
+
+        for row in &self.history {
+            let guess = solver
+                .dictionary()
+                .index_of(&row.guess)
+                .ok_or_else(|| SessionError::UnknownWord(row.guess.clone()))?;
+            if let Some(contradiction) = check_row(&solver, guess, row.feedback) {
+                return Err(SessionError::Contradiction(contradiction));
+            }
+            solver.apply_index(guess, row.feedback);
+        }
+
+        Ok(solver)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        let json =
+            std::fs::read_to_string(path).map_err(|err| SessionError::Io(err.to_string()))?;
+
+        serde_json::from_str(&json).map_err(|err| SessionError::Format(err.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        let json = serde_json::to_string_pretty(self).expect("Session serializes");
+
+        std::fs::write(path, json + "\n").map_err(|err| SessionError::Io(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::backend::CpuBackend;
+    use crate::consistency::Contradiction;
+    use crate::session::{Row, Session, SessionError};
+    use crate::strategy::{Minimax, Strategy};
+    use crate::{Dictionary, Histogram, Solver};
+
+    struct Magic;
+
+    impl Strategy for Magic {
+        fn name(&self) -> &'static str {
+            "magic"
+        }
+
+        fn score(&self, _bins: &Histogram, _total: f32) -> f32 {
+            0.0
+        }
+    }
+
+    fn solver() -> Solver {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Solver::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let mut solver = solver().with_strategy(Arc::new(Minimax)).with_noise(0.05);
+        solver.apply("cigar", "BBBBB".parse().unwrap()).unwrap();
+        solver.apply("tares", "BBGGY".parse().unwrap()).unwrap();
+
+        let session = Session::of(&solver).unwrap();
+        let json = serde_json::to_string(&session).unwrap();
+        assert!(json.contains(r#""history":[{"guess":"cigar","feedback":"BBBBB"}"#));
+        let session: Session = serde_json::from_str(&json).unwrap();
+
+        let restored = session.restore(self::solver()).unwrap();
+        assert_eq!(restored.history(), solver.history());
+        assert_eq!(restored.candidates(), solver.candidates());
+        assert_eq!(restored.weights(), solver.weights());
+        assert_eq!(restored.strategy().name(), "minimax");
+        assert_eq!(restored.noise(), Some(0.05));
+
+        let other = Solver::new(
+            Arc::new(Dictionary::parse("tares\nsorel").unwrap()),
+            Arc::new(CpuBackend),
+        );
+        assert_eq!(session.restore(other).err(), Some(SessionError::Dictionary));
+        let renamed = json.replace(r#""strategy":"minimax""#, r#""strategy":"magic""#);
+        assert!(serde_json::from_str::<Session>(&renamed).is_err());
+        assert_eq!(
+            Session::of(&solver.with_strategy(Arc::new(Magic))).err(),
+            Some(SessionError::Strategy("magic".to_string()))
+        );
+    }
+
+    #[test]
+    fn restore_contradictions() {
+        let mut session = Session::of(&solver()).unwrap();
+        let row = |guess: &str, feedback: &str| Row {
+            guess: guess.to_string(),
+            feedback: feedback.parse().unwrap(),
+        };
+        session.history = vec![row("tares", "BBBGY"), row("rebut", "GYBBB")];
+
+        let letter_count = Contradiction::LetterCount {
+            letter: 'r',
+            min: 1,
+            min_row: 2,
+            max: 0,
+            max_row: 1,
+        };
+        assert_eq!(
+            session.restore(solver()).err(),
+            Some(SessionError::Contradiction(letter_count))
+        );
+
+        // noisy games weigh the rows against each other, not within one
+        session.noise = Some(0.05);
+        assert!(session.restore(solver()).is_ok());
+        session.history.push(row("sissy", "BBYBB"));
+        assert_eq!(
+            session.restore(solver()).err(),
+            Some(SessionError::Contradiction(
+                Contradiction::YellowAfterGrey {
+                    row: 3,
+                    letter: 's'
+                }
+            ))
+        );
+    }
+}
@@ -1,20 +1,28 @@
 use wgpu::util::DeviceExt;
 
-use crate::{Patterns, Word, WORD_COUNT};
+use crate::backend::{EntropyBackend, Histogram};
+use crate::pattern::PATTERN_COUNT;
+use crate::Word;
 use std::{convert::TryInto, num::NonZeroU64};
-use wgpu::{BufferAsyncError, Device, Queue, RequestDeviceError, ShaderModule};
+use wgpu::{
+    Adapter, BindGroupLayout, BufferAsyncError, ComputePipeline, Device, Queue, RequestDeviceError,
+    ShaderModule,
+};
 
-async fn init_device() -> Result<(Device, Queue), RequestDeviceError> {
+const WORKGROUP_SIZE: u32 = 64;
+
+async fn request_adapter() -> Option<Adapter> {
     let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
-    let adapter = instance
+    instance
         .request_adapter(&wgpu::RequestAdapterOptions {
             power_preference: wgpu::PowerPreference::HighPerformance,
             force_fallback_adapter: false,
             compatible_surface: None,
         })
         .await
-        .expect("Failed to find an appropriate adapter");
+}
 
+async fn init_device(adapter: &Adapter) -> Result<(Device, Queue), RequestDeviceError> {
     adapter
         .request_device(
             &wgpu::DeviceDescriptor {
@@ -40,140 +48,205 @@ fn load_shader_module(device: &Device) -> ShaderModule {
     unsafe { device.create_shader_module_spirv(&shader_binary) }
 }
 
-pub async fn match_freq(
-    words: &[Word],
-    patterns: &[Patterns],
-) -> Result<Vec<f32>, BufferAsyncError> {
-    const RESULT_BUFFER_SIZE: usize = WORD_COUNT * 4; // f32 = u8 * 4
-    let (device, queue) = init_device().await.expect("Failed to create device");
-    let module = load_shader_module(&device);
+fn storage_entry(binding: u32, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        count: None,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            has_dynamic_offset: false,
+            min_binding_size: Some(NonZeroU64::new(1).unwrap()),
+            ty: wgpu::BufferBindingType::Storage { read_only },
+        },
+    }
+}
+
+/**
+    Keeps the device and compiled pipeline around, so that only the buffers
+    are re-created between the calls
+**/
+pub struct GpuBackend {
+    device: Device,
+    queue: Queue,
+    bind_group_layout: BindGroupLayout,
+    compute_pipeline: ComputePipeline,
+}
 
-    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        label: None,
-        entries: &[
-            // XXX - some graphics cards do not support empty bind layout groups, so
-            // create a dummy entry.
-            wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                count: None,
-                visibility: wgpu::ShaderStages::COMPUTE,
-                ty: wgpu::BindingType::Buffer {
-                    has_dynamic_offset: false,
-                    min_binding_size: Some(NonZeroU64::new(1).unwrap()),
-                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+impl GpuBackend {
+    pub fn new() -> Option<Self> {
+        futures::executor::block_on(Self::init())
+    }
+
+    async fn init() -> Option<Self> {
+        let adapter = request_adapter().await?;
+        let (device, queue) = init_device(&adapter).await.ok()?;
+        let module = load_shader_module(&device);
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                storage_entry(0, false),
+                storage_entry(1, true),
+                storage_entry(2, true),
+                storage_entry(3, true),
+                storage_entry(4, true),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: "main",
+        });
+
+        Some(GpuBackend {
+            device,
+            queue,
+            bind_group_layout,
+            compute_pipeline,
+        })
+    }
+
+    pub async fn match_freq(
+        &self,
+        guesses: &[Word],
+        candidates: &[Word],
+        weights: &[f32],
+    ) -> Result<Vec<Histogram>, BufferAsyncError> {
+        let device = &self.device;
+        let result_buffer_size = guesses.len() * PATTERN_COUNT * 4; // f32 = u8 * 4
+
+        let counts = [guesses.len() as u32, candidates.len() as u32];
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: result_buffer_size as wgpu::BufferAddress,
+            // Can be read to the CPU, and can be copied from the shader's storage buffer
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let result_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Histograms"),
+            size: result_buffer_size as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let words_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Words"),
+            contents: bytemuck::cast_slice(candidates),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let guesses_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Guesses"),
+            contents: bytemuck::cast_slice(guesses),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let weights_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Weights"),
+            contents: bytemuck::cast_slice(weights),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let counts_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Counts"),
+            contents: bytemuck::cast_slice(&counts),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: result_buffer.as_entire_binding(),
                 },
-            },
-            wgpu::BindGroupLayoutEntry {
-                binding: 1,
-                count: None,
-                visibility: wgpu::ShaderStages::COMPUTE,
-                ty: wgpu::BindingType::Buffer {
-                    has_dynamic_offset: false,
-                    min_binding_size: Some(NonZeroU64::new(1).unwrap()),
-                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: words_buffer.as_entire_binding(),
                 },
-            },
-            wgpu::BindGroupLayoutEntry {
-                binding: 2,
-                count: None,
-                visibility: wgpu::ShaderStages::COMPUTE,
-                ty: wgpu::BindingType::Buffer {
-                    has_dynamic_offset: false,
-                    min_binding_size: Some(NonZeroU64::new(1).unwrap()),
-                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: guesses_buffer.as_entire_binding(),
                 },
-            },
-        ],
-    });
-
-    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: None,
-        bind_group_layouts: &[&bind_group_layout],
-        push_constant_ranges: &[],
-    });
-
-    let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-        label: None,
-        layout: Some(&pipeline_layout),
-        module: &module,
-        entry_point: "main",
-    });
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: weights_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: counts_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        {
+            let mut cpass =
+                encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None });
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.set_pipeline(&self.compute_pipeline);
+            cpass.dispatch(
+                (guesses.len() as u32 + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE,
+                1,
+                1,
+            );
+        }
+
+        encoder.copy_buffer_to_buffer(
+            &result_buffer,
+            0,
+            &readback_buffer,
+            0,
+            result_buffer_size as wgpu::BufferAddress,
+        );
+
+        self.queue.submit(Some(encoder.finish()));
+        let buffer_slice = readback_buffer.slice(..);
+        let buffer_future = buffer_slice.map_async(wgpu::MapMode::Read);
+        device.poll(wgpu::Maintain::Wait);
+
+        buffer_future.await.map(|_| {
+            let bins: Vec<f32> = buffer_slice
+                .get_mapped_range()
+                .chunks_exact(4)
+                .map(|b| f32::from_ne_bytes(b.try_into().unwrap()))
+                .collect();
+
+            bins.chunks_exact(PATTERN_COUNT)
+                .map(|chunk| chunk.try_into().unwrap())
+                .collect()
+        })
+    }
+}
 
-    let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-        label: None,
-        size: RESULT_BUFFER_SIZE as wgpu::BufferAddress,
-        // Can be read to the CPU, and can be copied from the shader's storage buffer
-        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
-
-    let result_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Word Entropies"),
-        contents: &[0; RESULT_BUFFER_SIZE],
-        usage: wgpu::BufferUsages::STORAGE
-            | wgpu::BufferUsages::COPY_DST
-            | wgpu::BufferUsages::COPY_SRC,
-    });
-
-    let words_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Words"),
-        contents: bytemuck::cast_slice(words),
-        usage: wgpu::BufferUsages::STORAGE,
-    });
-
-    let patterns_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Patterns"),
-        contents: bytemuck::cast_slice(patterns),
-        usage: wgpu::BufferUsages::STORAGE,
-    });
-
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: None,
-        layout: &bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: result_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: words_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 2,
-                resource: patterns_buffer.as_entire_binding(),
-            },
-        ],
-    });
+impl EntropyBackend for GpuBackend {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
 
-    let mut encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    fn histograms(&self, guesses: &[Word], candidates: &[Word], weights: &[f32]) -> Vec<Histogram> {
+        // empty buffers can't be bound
+        if guesses.is_empty() || candidates.is_empty() {
+            return vec![[0.0; PATTERN_COUNT]; guesses.len()];
+        }
 
-    {
-        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None });
-        cpass.set_bind_group(0, &bind_group, &[]);
-        cpass.set_pipeline(&compute_pipeline);
-        cpass.dispatch(WORD_COUNT as u32 / 64, 1, 1);
+        futures::executor::block_on(self.match_freq(guesses, candidates, weights))
+            .expect("Failed to compute shader")
     }
-
-    encoder.copy_buffer_to_buffer(
-        &result_buffer,
-        0,
-        &readback_buffer,
-        0,
-        RESULT_BUFFER_SIZE as wgpu::BufferAddress,
-    );
-
-    queue.submit(Some(encoder.finish()));
-    let buffer_slice = readback_buffer.slice(..);
-    let buffer_future = buffer_slice.map_async(wgpu::MapMode::Read);
-    device.poll(wgpu::Maintain::Wait);
-
-    buffer_future.await.map(|_| {
-        buffer_slice
-            .get_mapped_range()
-            .chunks_exact(4)
-            .map(|b| f32::from_ne_bytes(b.try_into().unwrap()))
-            .collect::<Vec<_>>()
-    })
 }
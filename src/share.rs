@@ -0,0 +1,629 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::feedback::ParseFeedbackError;
+use crate::fivegram::FIVEGRAM;
+use crate::pattern::PATTERN_COUNT;
+use crate::word::WordBytes;
+use crate::{Dictionary, Feedback, Tile};
+
+const SQUARES: [char; 6] = ['🟩', '🟨', '⬛', '⬜', '🟧', '🟦'];
+
+/**
+    Share text of a game: the `Wordle 245 3/6*` header (when it's there)
+    and the coloured rows, letters of the guesses are never shared
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareGrid {
+    pub puzzle: Option<u32>,
+    pub hard_mode: bool,
+    pub rows: Vec<Feedback>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareError {
+    InvalidRow {
+        line: usize,
+        err: ParseFeedbackError,
+    },
+    NoRows,
+}
+
+impl Display for ShareError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareError::InvalidRow { line, err } => write!(f, "line {}: {}", line, err),
+            ShareError::NoRows => write!(f, "no rows of coloured squares found"),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/**
+    Header looks like `Wordle 1,234 4/6*`, `X/6` for the lost games and a
+    trailing `*` in hard mode
+**/
+fn parse_header(line: &str) -> Option<(Option<u32>, bool)> {
+    let mut words = line.split_whitespace();
+    if words.next()? != "Wordle" {
+        return None;
+    }
+
+    let puzzle = words.next()?.replace(',', "").parse().ok();
+    let score = words.next()?;
+
+    Some((puzzle, score.ends_with('*')))
+}
+
+impl FromStr for ShareGrid {
+    type Err = ShareError;
+
+    /**
+        Lines with the coloured squares are the rows, anything else but the
+        header is ignored (pasted chat text, links)
+    **/
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut grid = ShareGrid {
+            puzzle: None,
+            hard_mode: false,
+            rows: vec![],
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            if let Some((puzzle, hard_mode)) = parse_header(line) {
+                grid.puzzle = puzzle;
+                grid.hard_mode = hard_mode;
+            } else if line.chars().any(|c| SQUARES.contains(&c)) {
+                let row = line
+                    .parse()
+                    .map_err(|err| ShareError::InvalidRow { line: i + 1, err })?;
+                grid.rows.push(row);
+            }
+        }
+
+        if grid.rows.is_empty() {
+            return Err(ShareError::NoRows);
+        }
+
+        Ok(grid)
+    }
+}
+
+/**
+    Share text as the game puts it: the header, an empty line and the rows
+**/
+impl Display for ShareGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wordle ")?;
+        if let Some(puzzle) = self.puzzle {
+            let digits = puzzle.to_string();
+            for (i, digit) in digits.chars().enumerate() {
+                if i > 0 && (digits.len() - i) % 3 == 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", digit)?;
+            }
+            write!(f, " ")?;
+        }
+        if self.is_solved() {
+            write!(f, "{}/6", self.rows.len())?;
+        } else {
+            write!(f, "X/6")?;
+        }
+        writeln!(f, "{}\n", if self.hard_mode { "*" } else { "" })?;
+
+        for row in &self.rows {
+            writeln!(f, "{}", row.to_emoji())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ShareGrid {
+    pub fn is_solved(&self) -> bool {
+        self.rows.last().map_or(false, |row| row.is_solved())
+    }
+}
+
+/**
+    Answer which could have produced the grid: how many dictionary words give
+    each of the rows and how likely it is compared to the other answers
+**/
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnswerLikelihood {
+    pub answer: usize,
+    pub guesses_per_row: Vec<usize>,
+    pub probability: f64,
+}
+
+/**
+    Number of dictionary words giving each of the feedback to the answer
+**/
+fn feedback_counts(dictionary: &Dictionary, answer: usize) -> [usize; PATTERN_COUNT] {
+    let bytes = dictionary.bytes();
+    let mut counts = [0; PATTERN_COUNT];
+    for guess in bytes {
+        counts[Feedback::score(guess, &bytes[answer]).index()] += 1;
+    }
+
+    counts
+}
+
+/**
+    Answers for which every row of the grid is the feedback of some
+    dictionary word, most likely first.
+
+    Likelihood assumes that guesses are picked from the dictionary at random,
+    so the answer which many words give the rows to is a better explanation
+    than the one only a single odd word does. Rows before the last can't be
+    solved, a solved grid ends with the answer itself.
+**/
+pub fn infer_answers(
+    dictionary: &Dictionary,
+    answers: &[usize],
+    rows: &[Feedback],
+) -> Vec<AnswerLikelihood> {
+    let explain = |&answer: &usize| {
+        let counts = feedback_counts(dictionary, answer);
+        let guesses_per_row: Vec<usize> = rows.iter().map(|row| counts[row.index()]).collect();
+
+        let possible = guesses_per_row.iter().all(|&n| n > 0)
+            && rows.iter().rev().skip(1).all(|row| !row.is_solved());
+
+        possible.then(|| AnswerLikelihood {
+            answer,
+            guesses_per_row,
+            probability: 0.0,
+        })
+    };
+
+    #[cfg(feature = "parallel")]
+    let mut likely: Vec<AnswerLikelihood> = answers.par_iter().filter_map(explain).collect();
+    #[cfg(not(feature = "parallel"))]
+    let mut likely: Vec<AnswerLikelihood> = answers.iter().filter_map(explain).collect();
+
+    // log-likelihoods, the grids are long enough for the products to underflow
+    let log: Vec<f64> = likely
+        .iter()
+        .map(|l| l.guesses_per_row.iter().map(|&n| (n as f64).ln()).sum())
+        .collect();
+    let max = log.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let total: f64 = log.iter().map(|l| (l - max).exp()).sum();
+    for (l, log) in likely.iter_mut().zip(log) {
+        l.probability = (log - max).exp() / total;
+    }
+
+    let bytes = dictionary.bytes();
+    likely.sort_by(|a, b| {
+        b.probability
+            .partial_cmp(&a.probability)
+            .unwrap()
+            .then_with(|| bytes[a.answer].cmp(&bytes[b.answer]))
+    });
+
+    likely
+}
+
+/**
+    Why the grid can't be the record of a game with the answer
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridProblem {
+    TooManyRows(usize),
+    NoGuess {
+        row: usize,
+    },
+    SolvedEarly {
+        row: usize,
+    },
+    /**
+        Hard mode keeps the greens in place, the row lost one of them
+    **/
+    GreenDropped {
+        row: usize,
+        earlier: usize,
+        position: usize,
+    },
+    /**
+        Hard mode reuses the revealed letters, no choice of guesses does
+    **/
+    HintsNotReused,
+}
+
+impl Display for GridProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridProblem::TooManyRows(n) => write!(f, "{} rows, a game has at most {}", n, MAX_ROWS),
+            GridProblem::NoGuess { row } => write!(f, "row {}: no word gives this feedback", row),
+            GridProblem::SolvedEarly { row } => {
+                write!(f, "row {}: solved before the last row", row)
+            }
+            GridProblem::GreenDropped {
+                row,
+                earlier,
+                position,
+            } => write!(
+                f,
+                "row {}: hard mode keeps the green at position {} of row {}",
+                row, position, earlier
+            ),
+            GridProblem::HintsNotReused => {
+                write!(
+                    f,
+                    "no guesses reuse all of the revealed letters as hard mode requires"
+                )
+            }
+        }
+    }
+}
+
+/**
+    Guesses that fit the grid and the answer
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconstruction {
+    /**
+        Words which could have been the guess of each row
+    **/
+    pub guesses: Vec<Vec<usize>>,
+    /**
+        Number of the guess sequences fitting the whole grid
+    **/
+    pub sequences: u128,
+    pub problems: Vec<GridProblem>,
+}
+
+impl Reconstruction {
+    pub fn is_possible(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+const MAX_ROWS: usize = 6;
+
+/**
+    Letters of the guess coloured green or yellow, hard mode requires every
+    later guess to contain them
+**/
+type Hints = [u8; 26];
+
+fn hints(guess: &WordBytes, feedback: Feedback) -> Hints {
+    let mut hints = [0; 26];
+    for (&letter, tile) in guess.iter().zip(feedback.tiles()) {
+        if tile != Tile::Grey {
+            hints[(letter - b'a') as usize] += 1;
+        }
+    }
+
+    hints
+}
+
+fn contains(hints: &Hints, earlier: &Hints) -> bool {
+    hints.iter().zip(earlier).all(|(a, b)| a >= b)
+}
+
+/**
+    Per row groups of the guesses sharing the same hints, with the number of
+    hard mode sequences reaching them from the first row (`forward`) and
+    leading from them to the last one (`backward`)
+**/
+struct HintGroups {
+    groups: Vec<Vec<(Hints, u128)>>,
+    forward: Vec<Vec<u128>>,
+    backward: Vec<Vec<u128>>,
+}
+
+impl HintGroups {
+    fn new(dictionary: &Dictionary, rows: &[Feedback], guesses: &[Vec<usize>]) -> Self {
+        let bytes = dictionary.bytes();
+        let groups: Vec<Vec<(Hints, u128)>> = rows
+            .iter()
+            .zip(guesses)
+            .map(|(&row, words)| {
+                let mut groups: Vec<(Hints, u128)> = vec![];
+                for &g in words {
+                    let h = hints(&bytes[g], row);
+                    match groups.iter_mut().find(|(hg, _)| *hg == h) {
+                        Some((_, n)) => *n += 1,
+                        None => groups.push((h, 1)),
+                    }
+                }
+                groups
+            })
+            .collect();
+
+        let mut forward: Vec<Vec<u128>> = vec![];
+        for (i, row) in groups.iter().enumerate() {
+            let reach = row
+                .iter()
+                .map(|(h, n)| match i {
+                    0 => *n,
+                    _ => {
+                        let earlier = groups[i - 1].iter().zip(&forward[i - 1]);
+                        n * earlier
+                            .filter(|((e, _), _)| contains(h, e))
+                            .map(|(_, f)| f)
+                            .sum::<u128>()
+                    }
+                })
+                .collect();
+            forward.push(reach);
+        }
+
+        let mut backward: Vec<Vec<u128>> = vec![vec![]; groups.len()];
+        for i in (0..groups.len()).rev() {
+            backward[i] = groups[i]
+                .iter()
+                .map(|(h, _)| match groups.get(i + 1) {
+                    None => 1,
+                    Some(later) => later
+                        .iter()
+                        .zip(&backward[i + 1])
+                        .filter(|((l, _), _)| contains(l, h))
+                        .map(|((_, n), b)| n * b)
+                        .sum(),
+                })
+                .collect();
+        }
+
+        HintGroups {
+            groups,
+            forward,
+            backward,
+        }
+    }
+
+    fn sequences(&self) -> u128 {
+        self.forward.last().map_or(0, |last| last.iter().sum())
+    }
+
+    /**
+        Whether the guess is on any of the complete sequences
+    **/
+    fn is_viable(&self, row: usize, hints: &Hints) -> bool {
+        self.groups[row]
+            .iter()
+            .position(|(h, _)| h == hints)
+            .map_or(false, |g| {
+                self.forward[row][g] > 0 && self.backward[row][g] > 0
+            })
+    }
+}
+
+/**
+    Dictionary words the player could have guessed for every row of the grid:
+    the ones whose feedback to the answer is that row.
+
+    With `hard_mode` the greens must stay in place and the revealed letters
+    (at least as many of each) must be in every later guess, guesses which
+    don't fit any such sequence are left out.
+**/
+pub fn reconstruct(
+    dictionary: &Dictionary,
+    answer: usize,
+    rows: &[Feedback],
+    hard_mode: bool,
+) -> Reconstruction {
+    let bytes = dictionary.bytes();
+    let mut problems = vec![];
+
+    // the counts can overflow past that, the grid is impossible anyway
+    let too_long = rows.len() > MAX_ROWS;
+    if too_long {
+        problems.push(GridProblem::TooManyRows(rows.len()));
+    }
+
+    let mut guesses: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|&row| {
+            (0..dictionary.len())
+                .filter(|&g| Feedback::score(&bytes[g], &bytes[answer]) == row)
+                .collect()
+        })
+        .collect();
+
+    for (i, (row, words)) in rows.iter().zip(&guesses).enumerate() {
+        if words.is_empty() {
+            problems.push(GridProblem::NoGuess { row: i + 1 });
+        }
+        if row.is_solved() && i + 1 < rows.len() {
+            problems.push(GridProblem::SolvedEarly { row: i + 1 });
+        }
+    }
+
+    let mut sequences = if too_long {
+        0
+    } else {
+        guesses.iter().map(|words| words.len() as u128).product()
+    };
+
+    if hard_mode {
+        for (i, row) in rows.iter().enumerate() {
+            for (j, earlier) in rows[..i].iter().enumerate() {
+                let dropped = (0..FIVEGRAM)
+                    .find(|&p| earlier.tiles()[p] == Tile::Green && row.tiles()[p] != Tile::Green);
+                if let Some(p) = dropped {
+                    problems.push(GridProblem::GreenDropped {
+                        row: i + 1,
+                        earlier: j + 1,
+                        position: p + 1,
+                    });
+                }
+            }
+        }
+
+        if !too_long {
+            let groups = HintGroups::new(dictionary, rows, &guesses);
+            sequences = groups.sequences();
+            for (i, (&row, words)) in rows.iter().zip(guesses.iter_mut()).enumerate() {
+                words.retain(|&g| groups.is_viable(i, &hints(&bytes[g], row)));
+            }
+
+            if sequences == 0 && problems.is_empty() {
+                problems.push(GridProblem::HintsNotReused);
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        sequences = 0;
+    }
+
+    Reconstruction {
+        guesses,
+        sequences,
+        problems,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::share::{infer_answers, reconstruct, GridProblem, ShareError, ShareGrid};
+    use crate::{Dictionary, Feedback};
+
+    fn feedback(s: &str) -> Feedback {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_share_text() {
+        let text = "Wordle 1,234 3/6*\n\n⬛🟨⬛⬛⬛\n🟧🟧⬜🟦⬜\n🟩🟩🟩🟩🟩\nhttps://example.com";
+        let grid: ShareGrid = text.parse().unwrap();
+
+        assert_eq!(grid.puzzle, Some(1234));
+        assert!(grid.hard_mode);
+        assert!(grid.is_solved());
+        assert_eq!(
+            grid.rows,
+            vec![feedback("BYBBB"), feedback("GGBYB"), feedback("GGGGG")]
+        );
+
+        assert_eq!(
+            grid.to_string(),
+            "Wordle 1,234 3/6*\n\n⬛🟨⬛⬛⬛\n🟩🟩⬛🟨⬛\n🟩🟩🟩🟩🟩\n"
+        );
+        assert_eq!(grid.to_string().parse::<ShareGrid>(), Ok(grid));
+
+        let lost: ShareGrid = "Wordle 245 X/6\n⬛⬛⬛⬛⬛".parse().unwrap();
+        assert_eq!((lost.puzzle, lost.hard_mode), (Some(245), false));
+        assert!(!lost.is_solved());
+        assert_eq!(lost.to_string(), "Wordle 245 X/6\n\n⬛⬛⬛⬛⬛\n");
+
+        assert_eq!(
+            "Wordle 245 X/6".parse::<ShareGrid>(),
+            Err(ShareError::NoRows)
+        );
+        assert!(matches!(
+            "🟩🟩🟩🟩".parse::<ShareGrid>(),
+            Err(ShareError::InvalidRow { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn infers_answers() {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+        let answers: Vec<usize> = (0..dictionary.len()).collect();
+
+        // tares against sorel, then solved
+        let rows = [feedback("BBGGY"), feedback("GGGGG")];
+        let likely = infer_answers(&dictionary, &answers, &rows);
+        assert_eq!(likely.len(), 1);
+        assert_eq!(dictionary.word(likely[0].answer), "sorel");
+        assert_eq!(likely[0].guesses_per_row, vec![1, 1]);
+        assert_eq!(likely[0].probability, 1.0);
+
+        // all grey is what most words give to `humph`
+        let rows = [feedback("BBBBB"), feedback("GGGGG")];
+        let likely = infer_answers(&dictionary, &answers, &rows);
+        assert_eq!(dictionary.word(likely[0].answer), "humph");
+        assert!(likely
+            .windows(2)
+            .all(|w| w[0].probability >= w[1].probability));
+        let total: f64 = likely.iter().map(|l| l.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // solved in the middle of the game
+        let rows = [feedback("GGGGG"), feedback("GGGGG")];
+        assert!(infer_answers(&dictionary, &answers, &rows).is_empty());
+    }
+
+    #[test]
+    fn reconstructs_guesses() {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares\nsores").unwrap();
+        let sorel = dictionary.index_of("sorel").unwrap();
+        let words =
+            |ws: &[usize]| -> Vec<String> { ws.iter().map(|&w| dictionary.word(w)).collect() };
+
+        // cigar and awake only have the yellow `r` and `e`, tares gives BBGGY
+        let rows = [feedback("BBBBY"), feedback("BBGGY"), feedback("GGGGG")];
+        let found = reconstruct(&dictionary, sorel, &rows, false);
+        assert!(found.is_possible());
+        assert_eq!(words(&found.guesses[0]), vec!["cigar", "awake"]);
+        assert_eq!(words(&found.guesses[1]), vec!["tares"]);
+        assert_eq!(found.sequences, 2);
+        assert_eq!(reconstruct(&dictionary, sorel, &rows, true).sequences, 2);
+
+        // hard mode is fine with the revealed letters reused...
+        let rows = [feedback("BBGGY"), feedback("GGGGB"), feedback("GGGGG")];
+        let found = reconstruct(&dictionary, sorel, &rows, true);
+        assert_eq!(words(&found.guesses[1]), vec!["sores"]);
+        assert_eq!(found.sequences, 1);
+
+        // ...but not with the green `r` dropped or the letters forgotten
+        let rows = [feedback("BBGGY"), feedback("BBBBB"), feedback("GGGGG")];
+        let found = reconstruct(&dictionary, sorel, &rows, true);
+        assert_eq!(found.sequences, 0);
+        assert_eq!(
+            found.problems[0],
+            GridProblem::GreenDropped {
+                row: 2,
+                earlier: 1,
+                position: 3
+            }
+        );
+        assert!(reconstruct(&dictionary, sorel, &rows, false).is_possible());
+
+        // sissy keeps neither the `r` of cigar nor the `e` of awake
+        let rows = [feedback("BBBBY"), feedback("GBBBB"), feedback("GGGGG")];
+        assert_eq!(
+            reconstruct(&dictionary, sorel, &rows, true).problems,
+            vec![GridProblem::HintsNotReused]
+        );
+        assert_eq!(reconstruct(&dictionary, sorel, &rows, false).sequences, 2);
+
+        let rows = [feedback("GGGGG"), feedback("GGGGG")];
+        assert_eq!(
+            reconstruct(&dictionary, sorel, &rows, false).problems,
+            vec![GridProblem::SolvedEarly { row: 1 }]
+        );
+        let rows = [feedback("YYYYY")];
+        assert_eq!(
+            reconstruct(&dictionary, sorel, &rows, false).problems,
+            vec![GridProblem::NoGuess { row: 1 }]
+        );
+    }
+
+    #[test]
+    fn long_grid() {
+        // 12000 all grey guesses a row would overflow the count past 10 rows
+        let dictionary = Dictionary::bundled();
+        let cigar = dictionary.index_of("cigar").unwrap();
+        let mut rows = vec![feedback("BBBBB"); 20];
+        rows.push(feedback("GGGGG"));
+
+        for hard_mode in [false, true] {
+            let found = reconstruct(&dictionary, cigar, &rows, hard_mode);
+            assert_eq!(found.problems, vec![GridProblem::TooManyRows(21)]);
+            assert_eq!(found.sequences, 0);
+        }
+    }
+}
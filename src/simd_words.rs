@@ -0,0 +1,75 @@
+use core_simd::{LaneCount, SupportedLaneCount};
+
+use crate::Word;
+
+pub type Simd<const LANES: usize> = core_simd::Simd<u32, LANES>;
+pub type WeightSimd<const LANES: usize> = core_simd::Simd<f32, LANES>;
+pub type Mask<const LANES: usize> = core_simd::Mask<i32, LANES>;
+
+/**
+    Transposed (structure-of-arrays) chunk of `LANES` words, so that one
+    guess is scored against many words at once.
+
+    Last chunk is padded with zero-weight words.
+**/
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SimdWords<const LANES: usize>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    pub word: Simd<LANES>,
+    pub letters: Simd<LANES>,
+    pub weights: WeightSimd<LANES>,
+}
+
+impl<const LANES: usize> SimdWords<LANES>
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    pub fn from_words(words: &[Word], weights: &[f32]) -> Vec<Self> {
+        words
+            .chunks(LANES)
+            .zip(weights.chunks(LANES))
+            .map(|(words, weights)| {
+                let mut word = [0u32; LANES];
+                let mut letters = [0u32; LANES];
+                let mut lane_weights = [0f32; LANES];
+
+                for (i, (w, &weight)) in words.iter().zip(weights).enumerate() {
+                    word[i] = w.fivegram.word;
+                    letters[i] = w.letters.set;
+                    lane_weights[i] = weight;
+                }
+
+                SimdWords {
+                    word: Simd::from_array(word),
+                    letters: Simd::from_array(letters),
+                    weights: WeightSimd::from_array(lane_weights),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::SimdWords;
+    use crate::Word;
+
+    #[test]
+    fn pads_last_chunk() {
+        let words: Vec<Word> = ["hello", "world", "sorel", "tares", "cigar"]
+            .iter()
+            .map(|w| Word::from_str(w))
+            .collect();
+
+        let chunks = SimdWords::<4>::from_words(&words, &[1.0; 5]);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].weights.to_array(), [1.0; 4]);
+        assert_eq!(chunks[1].weights.to_array(), [1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(chunks[1].word.to_array()[0], words[4].fivegram.word);
+    }
+}
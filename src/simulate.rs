@@ -0,0 +1,128 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{Feedback, Solver};
+
+/**
+    Guesses the solver made for the answer along with their feedback
+**/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub answer: usize,
+    pub guesses: Vec<(usize, Feedback)>,
+}
+
+impl Game {
+    pub fn is_solved(&self) -> bool {
+        self.guesses
+            .last()
+            .map_or(false, |(_, feedback)| feedback.is_solved())
+    }
+
+    pub fn turns(&self) -> usize {
+        self.guesses.len()
+    }
+}
+
+/**
+    Best guess of the solver, the last candidate once there's only one left
+**/
+fn next_guess(solver: &Solver) -> usize {
+    match solver.candidates() {
+        [answer] => *answer,
+        _ => solver.top(1)[0].0,
+    }
+}
+
+/**
+    Plays the game to the answer from the solver state, `opening` skips the
+    ranking of the first guess (it's the same for every answer)
+**/
+pub fn play(solver: &Solver, answer: usize, opening: Option<usize>, max_guesses: usize) -> Game {
+    let mut solver = solver.clone();
+    let mut guess = opening.unwrap_or_else(|| next_guess(&solver));
+    let mut guesses = vec![];
+
+    while guesses.len() < max_guesses {
+        let bytes = solver.dictionary().bytes();
+        let feedback = Feedback::score(&bytes[guess], &bytes[answer]);
+        guesses.push((guess, feedback));
+        if feedback.is_solved() || guesses.len() == max_guesses {
+            break;
+        }
+
+        solver.apply_index(guess, feedback);
+        guess = next_guess(&solver);
+    }
+
+    Game { answer, guesses }
+}
+
+/**
+    One game per answer, all starting with the same (ranked once) opening
+**/
+pub fn simulate(solver: &Solver, answers: &[usize], max_guesses: usize) -> Vec<Game> {
+    if max_guesses == 0 {
+        return answers
+            .iter()
+            .map(|&answer| Game {
+                answer,
+                guesses: vec![],
+            })
+            .collect();
+    }
+
+    let opening = next_guess(solver);
+    let play = |&answer: &usize| play(solver, answer, Some(opening), max_guesses);
+
+    #[cfg(feature = "parallel")]
+    return answers.par_iter().map(play).collect();
+
+    #[cfg(not(feature = "parallel"))]
+    return answers.iter().map(play).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::backend::CpuBackend;
+    use crate::simulate::{play, simulate};
+    use crate::{Dictionary, Solver};
+
+    fn solver() -> Solver {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Solver::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    #[test]
+    fn solves_every_answer() {
+        let solver = solver();
+        let answers: Vec<usize> = (0..solver.dictionary().len()).collect();
+        let games = simulate(&solver, &answers, 6);
+
+        assert_eq!(games.len(), answers.len());
+        for (game, &answer) in games.iter().zip(&answers) {
+            assert!(game.is_solved());
+            assert_eq!(game.answer, answer);
+            assert_eq!(game.guesses.last().unwrap().0, answer);
+        }
+        // same opening for every game
+        assert!(games
+            .iter()
+            .all(|g| g.guesses[0].0 == games[0].guesses[0].0));
+    }
+
+    #[test]
+    fn gives_up_after_max_guesses() {
+        let solver = solver();
+        let game = play(&solver, 3, Some(0), 1);
+
+        assert_eq!(game.turns(), 1);
+        assert!(!game.is_solved());
+        assert_eq!(simulate(&solver, &[3], 0)[0].turns(), 0);
+    }
+}
@@ -0,0 +1,602 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::backend::{EntropyBackend, Histogram};
+use crate::book::Book;
+use crate::report::GuessReport;
+use crate::strategy::{top_k, Entropy, Strategy};
+use crate::word::WordBytes;
+use crate::word_set::WordSet;
+use crate::{Dictionary, Feedback, Word};
+
+/**
+    State of one game: guesses made so far with their feedback and the
+    dictionary words still consistent with all of them
+**/
+#[derive(Clone)]
+pub struct Solver {
+    dictionary: Arc<Dictionary>,
+    backend: Arc<dyn EntropyBackend>,
+    strategy: Arc<dyn Strategy>,
+    book: Option<Arc<Book>>,
+    history: Vec<(usize, Feedback)>,
+    candidate_set: WordSet,
+    candidates: Vec<usize>,
+    /**
+        Likelihood of each candidate, the most likely one at 1
+    **/
+    weights: Vec<f32>,
+    noise: Option<f32>,
+}
+
+/**
+    Candidates less likely than this share of the most likely one are
+    dropped in the noisy mode, about three mistyped tiles at 2% error rate
+**/
+pub const NOISE_CUTOFF: f32 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverError {
+    UnknownWord(String),
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::UnknownWord(word) => write!(f, "`{}` is not in the dictionary", word),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+impl Solver {
+    pub fn new(dictionary: Arc<Dictionary>, backend: Arc<dyn EntropyBackend>) -> Self {
+        let candidates = (0..dictionary.len()).collect();
+
+        Solver {
+            weights: vec![1.0; dictionary.len()],
+            noise: None,
+            candidate_set: WordSet::full(dictionary.len()),
+            dictionary,
+            backend,
+            strategy: Arc::new(Entropy),
+            book: None,
+            history: vec![],
+            candidates,
+        }
+    }
+
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: Arc<dyn Strategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /**
+        Rankings are looked up in the book first, as long as it was built
+        for the same dictionary and strategy (see `Book::check`)
+    **/
+    #[must_use]
+    pub fn with_book(mut self, book: Arc<Book>) -> Self {
+        self.book = Some(book);
+        self
+    }
+
+    /**
+        Noisy mode: each tile of the entered feedback is wrong with the
+        probability `error` (0.001 to 0.5). Candidates are weighted by how
+        likely the history is for them instead of being dropped on the first
+        mismatch, so a mistyped row is outvoted by the later ones.
+    **/
+    #[must_use]
+    pub fn with_noise(mut self, error: f32) -> Self {
+        // the likelihoods of a few wrong tiles stay within f32
+        self.noise = Some(error.clamp(0.001, 0.5));
+        self
+    }
+
+    pub fn noise(&self) -> Option<f32> {
+        self.noise
+    }
+
+    pub fn clear_book(&mut self) {
+        self.book = None;
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn backend(&self) -> &dyn EntropyBackend {
+        self.backend.as_ref()
+    }
+
+    pub fn strategy(&self) -> &dyn Strategy {
+        self.strategy.as_ref()
+    }
+
+    pub fn history(&self) -> &[(usize, Feedback)] {
+        &self.history
+    }
+
+    /**
+        Dictionary indices of the words consistent with the history
+    **/
+    pub fn candidates(&self) -> &[usize] {
+        &self.candidates
+    }
+
+    /**
+        Relative likelihoods of the candidates, in the same order. All 1
+        unless the solver is noisy.
+    **/
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /**
+        Candidates with their probability of being the answer, most likely
+        first
+    **/
+    pub fn likely_candidates(&self) -> Vec<(usize, f32)> {
+        let total = self.total_weight();
+        let mut likely: Vec<(usize, f32)> = self
+            .candidates
+            .iter()
+            .zip(&self.weights)
+            .map(|(&c, &w)| (c, w / total))
+            .collect();
+        likely.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+
+        likely
+    }
+
+    /**
+        Sum of the candidate weights, their count without noise
+    **/
+    pub fn total_weight(&self) -> f32 {
+        self.weights.iter().sum()
+    }
+
+    pub fn apply(&mut self, guess: &str, feedback: Feedback) -> Result<(), SolverError> {
+        let guess = self
+            .dictionary
+            .index_of(guess)
+            .ok_or_else(|| SolverError::UnknownWord(guess.to_string()))?;
+
+        self.apply_index(guess, feedback);
+        Ok(())
+    }
+
+    /**
+        Same candidates as a set, see `candidates` for the indices
+    **/
+    pub fn candidate_set(&self) -> &WordSet {
+        &self.candidate_set
+    }
+
+    /**
+        Intersects the candidates with the set of the words giving the
+        feedback, the sets of the opening rows are cached in the dictionary's
+        `FeedbackIndex`
+    **/
+    pub fn apply_index(&mut self, guess: usize, feedback: Feedback) {
+        self.history.push((guess, feedback));
+        if let Some(error) = self.noise {
+            return self.weigh(guess, feedback, error);
+        }
+
+        let matching = self.dictionary.feedback_index().matching(
+            &self.dictionary,
+            guess,
+            feedback,
+            &self.candidate_set,
+        );
+        self.candidate_set.intersect_with(&matching);
+        self.candidates = self.candidate_set.iter().collect();
+        self.weights = vec![1.0; self.candidates.len()];
+    }
+
+    /**
+        Scales each candidate by the likelihood of the feedback, every wrong
+        tile is one of the two other colours
+    **/
+    fn weigh(&mut self, guess: usize, feedback: Feedback, error: f32) {
+        let bytes = self.dictionary.bytes();
+        let mistyped = error / 2.0 / (1.0 - error);
+
+        for (w, &c) in self.weights.iter_mut().zip(&self.candidates) {
+            let wrong = Feedback::score(&bytes[guess], &bytes[c]).distance(feedback);
+            *w *= mistyped.powi(wrong as i32);
+        }
+
+        let most = self.weights.iter().copied().fold(0.0, f32::max);
+        let kept: Vec<(usize, f32)> = self
+            .candidates
+            .iter()
+            .zip(&self.weights)
+            .filter(|&(_, &w)| w >= most * NOISE_CUTOFF)
+            .map(|(&c, &w)| (c, w / most))
+            .collect();
+
+        self.candidate_set = WordSet::from_indices(self.dictionary.len(), kept.iter().map(|k| k.0));
+        self.candidates = kept.iter().map(|k| k.0).collect();
+        self.weights = kept.iter().map(|k| k.1).collect();
+    }
+
+    /**
+        Drops the last guess and rebuilds the candidates from the remaining history
+    **/
+    pub fn undo(&mut self) -> Option<(usize, Feedback)> {
+        let last = self.history.pop()?;
+        let history = std::mem::take(&mut self.history);
+
+        self.candidate_set = WordSet::full(self.dictionary.len());
+        self.candidates = (0..self.dictionary.len()).collect();
+        self.weights = vec![1.0; self.dictionary.len()];
+        for (guess, feedback) in history {
+            self.apply_index(guess, feedback);
+        }
+
+        Some(last)
+    }
+
+    /**
+        Pattern histogram of every dictionary word as the next guess over
+        the remaining candidates
+    **/
+    pub fn histograms(&self) -> Vec<Histogram> {
+        let candidates: Vec<Word> = self
+            .candidates
+            .iter()
+            .map(|&c| self.dictionary.words()[c])
+            .collect();
+
+        self.backend
+            .histograms(self.dictionary.words(), &candidates, &self.weights)
+    }
+
+    /**
+        Weighted feedback histogram of a single guess over the candidates
+    **/
+    pub fn histogram(&self, guess: usize) -> Histogram {
+        let candidates: Vec<Word> = self
+            .candidates
+            .iter()
+            .map(|&c| self.dictionary.words()[c])
+            .collect();
+
+        self.backend.histograms(
+            &self.dictionary.words()[guess..=guess],
+            &candidates,
+            &self.weights,
+        )[0]
+    }
+
+    /**
+        Strategy score of every dictionary word as the next guess
+    **/
+    pub fn scores(&self) -> Vec<f32> {
+        let bytes = self.dictionary.bytes();
+        let candidates: Vec<WordBytes> = self.candidates.iter().map(|&c| bytes[c]).collect();
+        if let Some(scores) = self.strategy.score_words(bytes, &candidates, &self.weights) {
+            return scores;
+        }
+
+        let total = self.total_weight();
+
+        self.histograms()
+            .iter()
+            .map(|bins| self.strategy.score(bins, total))
+            .collect()
+    }
+
+    /**
+        `n` best next guesses with their scores, best first, ties by word
+    **/
+    pub fn top(&self, n: usize) -> Vec<(usize, f32)> {
+        if let Some(reports) = self.book_reports(n) {
+            return reports
+                .iter()
+                .filter_map(|r| Some((self.dictionary.index_of(&r.word)?, r.score)))
+                .collect();
+        }
+
+        let scores = self.scores();
+
+        top_k(self.strategy.as_ref(), &scores, self.dictionary.bytes(), n)
+            .into_iter()
+            .map(|i| (i, scores[i]))
+            .collect()
+    }
+
+    /**
+        Same ranking as `top`, with the bucket metrics of every guess
+    **/
+    pub fn report(&self, n: usize) -> Vec<GuessReport> {
+        if let Some(reports) = self.book_reports(n) {
+            return reports.to_vec();
+        }
+
+        let histograms = self.histograms();
+        let total = self.total_weight();
+        let scores: Vec<f32> = histograms
+            .iter()
+            .map(|bins| self.strategy.score(bins, total))
+            .collect();
+
+        top_k(self.strategy.as_ref(), &scores, self.dictionary.bytes(), n)
+            .into_iter()
+            .enumerate()
+            .map(|(rank, i)| {
+                GuessReport::new(
+                    rank + 1,
+                    self.dictionary.word(i),
+                    scores[i],
+                    &histograms[i],
+                    total,
+                    self.candidate_set.contains(i),
+                )
+            })
+            .collect()
+    }
+
+    /**
+        Bucket metrics of a single guess, ranked first
+    **/
+    pub fn guess_report(&self, guess: usize) -> GuessReport {
+        let bins = self.histogram(guess);
+        let total = self.total_weight();
+
+        GuessReport::new(
+            1,
+            self.dictionary.word(guess),
+            self.strategy.score(&bins, total),
+            &bins,
+            total,
+            self.candidate_set.contains(guess),
+        )
+    }
+
+    /**
+        Book rankings of the game so far, when there are at least `n` of them
+    **/
+    fn book_reports(&self, n: usize) -> Option<&[GuessReport]> {
+        // the book follows the exact filtering
+        let book = self.book.as_ref().filter(|_| self.noise.is_none())?;
+        if book.dictionary != self.dictionary.fingerprint() || book.strategy != self.strategy.name()
+        {
+            return None;
+        }
+
+        book.lookup(&self.dictionary, &self.history)?
+            .reports
+            .get(..n)
+    }
+
+    /**
+        Candidates grouped by the feedback they would give to the guess,
+        largest groups first
+    **/
+    pub fn explain(&self, guess: usize) -> Vec<(Feedback, Vec<usize>)> {
+        let bytes = self.dictionary.bytes();
+        let mut buckets: Vec<(Feedback, Vec<usize>)> = vec![];
+
+        for &c in &self.candidates {
+            let feedback = Feedback::score(&bytes[guess], &bytes[c]);
+            match buckets.iter_mut().find(|(f, _)| *f == feedback) {
+                Some((_, words)) => words.push(c),
+                None => buckets.push((feedback, vec![c])),
+            }
+        }
+
+        buckets.sort_by(|(fa, a), (fb, b)| b.len().cmp(&a.len()).then(fa.cmp(fb)));
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use crate::backend::CpuBackend;
+    use crate::pattern::PATTERN_COUNT;
+    use crate::strategy::Minimax;
+    use crate::{Dictionary, Feedback, Solver};
+
+    fn solver() -> Solver {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        Solver::new(Arc::new(dictionary), Arc::new(CpuBackend))
+    }
+
+    fn candidates(solver: &Solver) -> Vec<String> {
+        solver
+            .candidates()
+            .iter()
+            .map(|&c| solver.dictionary().word(c))
+            .collect()
+    }
+
+    #[test]
+    fn apply_filters_candidates() {
+        let mut solver = solver();
+
+        solver.apply("tares", "BBBBY".parse().unwrap()).unwrap();
+        assert_eq!(candidates(&solver), vec!["sissy"]);
+
+        solver.apply("sissy", Feedback::SOLVED).unwrap();
+        assert_eq!(candidates(&solver), vec!["sissy"]);
+        assert_eq!(solver.history().len(), 2);
+    }
+
+    // Bitset filtering keeps the same words as scoring every candidate
+    #[test]
+    fn proptest_filter_matches_scoring() {
+        let dictionary = Arc::new(Dictionary::bundled());
+        let solver = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let bytes = dictionary.bytes();
+
+        proptest!(|(answer in 0..dictionary.len(), guesses in vec(0..dictionary.len(), 1..4))| {
+            let mut solver = solver.clone();
+            let mut expected: Vec<usize> = (0..dictionary.len()).collect();
+            for &g in &guesses {
+                let feedback = Feedback::score(&bytes[g], &bytes[answer]);
+                solver.apply_index(g, feedback);
+                expected.retain(|&c| Feedback::score(&bytes[g], &bytes[c]) == feedback);
+            }
+
+            assert_eq!(solver.candidates(), &expected[..]);
+            assert_eq!(solver.candidate_set().count(), expected.len());
+            assert!(solver.candidate_set().contains(answer));
+        });
+    }
+
+    #[test]
+    fn solvers_share_feedback_index() {
+        let dictionary = Arc::new(Dictionary::bundled());
+        let mut first = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+        let mut second = Solver::new(dictionary.clone(), Arc::new(CpuBackend));
+
+        first.apply("tares", "BBGGY".parse().unwrap()).unwrap();
+        first.apply("sorel", "GGGGB".parse().unwrap()).unwrap();
+        assert_eq!(dictionary.feedback_index().len(), 1);
+        second.apply("tares", "BBGGY".parse().unwrap()).unwrap();
+        assert_eq!(dictionary.feedback_index().len(), 1);
+        assert_eq!(first.history().len(), 2);
+        second.apply("sorel", "GGGGB".parse().unwrap()).unwrap();
+        assert_eq!(first.candidates(), second.candidates());
+    }
+
+    #[test]
+    fn undo_restores_candidates() {
+        let mut solver = solver();
+
+        solver.apply("cigar", "BBBBY".parse().unwrap()).unwrap();
+        let after_first = candidates(&solver);
+        solver.apply("rebut", "BBBBB".parse().unwrap()).unwrap();
+
+        assert_eq!(solver.undo(), Some((1, "BBBBB".parse().unwrap())));
+        assert_eq!(candidates(&solver), after_first);
+        solver.undo();
+        assert_eq!(solver.candidates().len(), 7);
+        assert_eq!(solver.undo(), None);
+    }
+
+    #[test]
+    fn noisy_feedback() {
+        let mut exact = solver();
+        let mut noisy = solver().with_noise(0.05);
+        let sorel = noisy.dictionary().index_of("sorel").unwrap();
+
+        // the `r` of cigar was yellow
+        for (guess, feedback) in [("cigar", "BBBBB"), ("tares", "BBGGY")] {
+            exact.apply(guess, feedback.parse().unwrap()).unwrap();
+            noisy.apply(guess, feedback.parse().unwrap()).unwrap();
+        }
+        assert!(exact.candidates().is_empty());
+
+        let likely = noisy.likely_candidates();
+        assert_eq!(likely[0].0, sorel);
+        assert!(likely[0].1 > 0.9);
+        assert_eq!(noisy.weights().len(), noisy.candidates().len());
+        assert!((likely.iter().map(|l| l.1).sum::<f32>() - 1.0).abs() < 1e-5);
+        assert_eq!(noisy.report(1).len(), 1);
+
+        noisy.undo();
+        assert_eq!(noisy.likely_candidates()[0].0, 3);
+        assert!(noisy.candidate_set().contains(sorel));
+    }
+
+    #[test]
+    fn unknown_guess() {
+        assert!(solver().apply("zzzzz", Feedback::SOLVED).is_err());
+    }
+
+    #[test]
+    fn scores_over_candidates() {
+        let mut solver = solver().with_strategy(Arc::new(Minimax));
+        assert_eq!(solver.strategy().name(), "minimax");
+
+        solver.apply("cigar", "BBBBB".parse().unwrap()).unwrap();
+        assert_eq!(candidates(&solver), vec!["humph"]);
+        assert_eq!(solver.scores(), vec![1.0; 7]);
+        // all equal, so alphabetical
+        let top: Vec<String> = solver
+            .top(3)
+            .iter()
+            .map(|&(i, _)| solver.dictionary().word(i))
+            .collect();
+        assert_eq!(top, vec!["awake", "cigar", "humph"]);
+
+        let report = solver.report(7);
+        assert_eq!(report.len(), 7);
+        assert_eq!(report[0].word, "awake");
+        assert_eq!(report[2].rank, 3);
+        assert_eq!(report.iter().filter(|r| r.possible_answer).count(), 1);
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn backends_agree() {
+        use crate::backend::{SimdBackend, TransposedBackend};
+
+        let cpu = solver();
+        let simd = Solver::new(
+            Arc::new(cpu.dictionary().clone()),
+            Arc::new(SimdBackend::detect()),
+        );
+        let transposed = Solver::new(
+            Arc::new(cpu.dictionary().clone()),
+            Arc::new(TransposedBackend::detect()),
+        );
+
+        assert_eq!(cpu.histograms(), simd.histograms());
+        assert_eq!(cpu.histograms(), transposed.histograms());
+        assert_eq!(cpu.top(5), simd.top(5));
+    }
+
+    #[test]
+    fn explain_groups_candidates() {
+        let solver = solver();
+        let tares = solver.dictionary().index_of("tares").unwrap();
+
+        let buckets = solver.explain(tares);
+        assert_eq!(buckets.iter().map(|(_, w)| w.len()).sum::<usize>(), 7);
+        assert!(buckets.windows(2).all(|w| w[0].1.len() >= w[1].1.len()));
+        assert!(buckets
+            .iter()
+            .any(|(f, w)| f.to_string() == "BBBBB" && w == &vec![3]));
+        assert!(buckets
+            .iter()
+            .any(|(f, w)| f.is_solved() && w == &vec![tares]));
+    }
+
+    // Repeated letters rank by the same feedback the candidates are filtered by
+    #[test]
+    fn histograms_match_explain() {
+        let solver = Solver::new(Arc::new(Dictionary::bundled()), Arc::new(CpuBackend));
+        let histograms = solver.histograms();
+
+        for word in ["sissy", "geese", "llama", "tares"] {
+            let guess = solver.dictionary().index_of(word).unwrap();
+            let mut expected = [0.0; PATTERN_COUNT];
+            for (feedback, words) in solver.explain(guess) {
+                expected[feedback.index()] = words.len() as f32;
+            }
+
+            assert_eq!(histograms[guess], expected, "{}", word);
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{histogram_to_entropy, Histogram};
+use crate::feedback::{Feedback, Tile};
+use crate::fivegram::FIVEGRAM;
+use crate::pattern::PATTERN_COUNT;
+use crate::word::WordBytes;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Goal {
+    Maximise,
+    Minimise,
+}
+
+/**
+    Turns the weighted pattern histogram of a guess into a score, `total` is
+    the sum of the candidate weights (priors), so that the histogram can be
+    read as a probability distribution.
+
+    Backends only build histograms, so every strategy works with all of them.
+**/
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn goal(&self) -> Goal {
+        Goal::Maximise
+    }
+
+    fn score(&self, bins: &Histogram, total: f32) -> f32;
+
+    /**
+        Scores of the guesses straight from the weighted candidates, for the
+        heuristics that don't need the histograms. `None` ranks through the
+        backend, the scores have to agree with `score` otherwise.
+    **/
+    fn score_words(
+        &self,
+        _guesses: &[WordBytes],
+        _candidates: &[WordBytes],
+        _weights: &[f32],
+    ) -> Option<Vec<f32>> {
+        None
+    }
+
+    /**
+        `Less` if `a` is the better score
+    **/
+    fn compare(&self, a: f32, b: f32) -> Ordering {
+        let ord = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+
+        match self.goal() {
+            Goal::Maximise => ord.reverse(),
+            Goal::Minimise => ord,
+        }
+    }
+}
+
+/**
+    Indices of the `k` best scores, best first. Equal scores are ordered by
+    the word, so the ranking only depends on the scores and not on the order
+    the backend produced them in.
+**/
+pub fn top_k(strategy: &dyn Strategy, scores: &[f32], words: &[WordBytes], k: usize) -> Vec<usize> {
+    let compare = |&a: &usize, &b: &usize| {
+        strategy
+            .compare(scores[a], scores[b])
+            .then_with(|| words[a].cmp(&words[b]))
+    };
+
+    let mut idx: Vec<usize> = (0..scores.len()).collect();
+    let k = k.min(idx.len());
+    if k < idx.len() && k > 0 {
+        idx.select_nth_unstable_by(k - 1, compare);
+    }
+    idx.truncate(k);
+    idx.sort_unstable_by(compare);
+
+    idx
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    Entropy,
+    Minimax,
+    ExpectedSize,
+    MostBuckets,
+    Frequency,
+}
+
+impl StrategyKind {
+    pub fn build(self) -> Arc<dyn Strategy> {
+        match self {
+            StrategyKind::Entropy => Arc::new(Entropy),
+            StrategyKind::Minimax => Arc::new(Minimax),
+            StrategyKind::ExpectedSize => Arc::new(ExpectedSize),
+            StrategyKind::MostBuckets => Arc::new(MostBuckets),
+            StrategyKind::Frequency => Arc::new(Frequency),
+        }
+    }
+}
+
+/**
+    Expected information in bits, Shannon entropy of the pattern distribution
+**/
+pub struct Entropy;
+
+impl Strategy for Entropy {
+    fn name(&self) -> &'static str {
+        "entropy"
+    }
+
+    fn score(&self, bins: &Histogram, total: f32) -> f32 {
+        histogram_to_entropy(bins, total)
+    }
+}
+
+/**
+    Size of the largest bucket, the worst case of what's left after the guess
+**/
+pub struct Minimax;
+
+impl Strategy for Minimax {
+    fn name(&self) -> &'static str {
+        "minimax"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::Minimise
+    }
+
+    fn score(&self, bins: &Histogram, _total: f32) -> f32 {
+        bins.iter().copied().fold(0.0, f32::max)
+    }
+}
+
+/**
+    Expected number of candidates remaining, Σn²/N
+**/
+pub struct ExpectedSize;
+
+impl Strategy for ExpectedSize {
+    fn name(&self) -> &'static str {
+        "expected-size"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::Minimise
+    }
+
+    fn score(&self, bins: &Histogram, total: f32) -> f32 {
+        bins.iter().map(|&n| n * n).sum::<f32>() / total
+    }
+}
+
+/**
+    Number of non-empty buckets, the guess splits candidates in as many groups
+**/
+pub struct MostBuckets;
+
+impl Strategy for MostBuckets {
+    fn name(&self) -> &'static str {
+        "most-buckets"
+    }
+
+    fn score(&self, bins: &Histogram, _total: f32) -> f32 {
+        bins.iter().filter(|&&n| n != 0.0).count() as f32
+    }
+}
+
+/**
+    Cheap heuristic: expected number of the guess letters which turn out to be
+    present (green or yellow) in the answer, greens counted twice. Ranked from
+    the per-position and per-letter frequencies of the candidates, without
+    the backend.
+**/
+pub struct Frequency;
+
+impl Strategy for Frequency {
+    fn name(&self) -> &'static str {
+        "frequency"
+    }
+
+    fn score(&self, bins: &Histogram, total: f32) -> f32 {
+        (0..PATTERN_COUNT)
+            .filter(|&i| bins[i] != 0.0)
+            .map(|i| {
+                let tiles = Feedback::from_index(i).unwrap().tiles();
+                let present = tiles.iter().filter(|&&tile| tile != Tile::Grey).count();
+                let green = tiles.iter().filter(|&&tile| tile == Tile::Green).count();
+
+                bins[i] * (present + green) as f32
+            })
+            .sum::<f32>()
+            / total
+    }
+
+    /**
+        The `k`-th copy of a letter is coloured when the answer has at least
+        `k` of them, so the expected present letters add up from the weight of
+        the candidates with at least `k` of each letter
+    **/
+    fn score_words(
+        &self,
+        guesses: &[WordBytes],
+        candidates: &[WordBytes],
+        weights: &[f32],
+    ) -> Option<Vec<f32>> {
+        let total: f32 = weights.iter().sum();
+        let mut at = [[0.0; 26]; FIVEGRAM];
+        let mut at_least = [[0.0; FIVEGRAM]; 26];
+
+        for (word, &weight) in candidates.iter().zip(weights) {
+            let mut counts = [0; 26];
+            for (i, &b) in word.iter().enumerate() {
+                let l = (b - b'a') as usize;
+                at[i][l] += weight;
+                at_least[l][counts[l]] += weight;
+                counts[l] += 1;
+            }
+        }
+
+        let scores = guesses
+            .iter()
+            .map(|guess| {
+                let mut counts = [0; 26];
+                let mut score = 0.0;
+                for (i, &b) in guess.iter().enumerate() {
+                    let l = (b - b'a') as usize;
+                    score += at[i][l] + at_least[l][counts[l]];
+                    counts[l] += 1;
+                }
+
+                score / total
+            })
+            .collect();
+
+        Some(scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::cmp::Ordering;
+
+    use proptest::prelude::*;
+
+    use crate::backend::{CpuBackend, EntropyBackend};
+    use crate::pattern::PATTERN_COUNT;
+    use crate::strategy::{top_k, Entropy, ExpectedSize, Frequency, MostBuckets, Strategy};
+    use crate::strategy::{Minimax, StrategyKind};
+    use crate::word::{wordbytes_from_str, WordBytes};
+    use crate::{Dictionary, Word};
+
+    fn histogram(bins: &[(usize, f32)]) -> [f32; PATTERN_COUNT] {
+        let mut histogram = [0.0; PATTERN_COUNT];
+        for &(i, n) in bins {
+            histogram[i] = n;
+        }
+
+        histogram
+    }
+
+    #[test]
+    fn scores() {
+        // 8 candidates: 4 solved, 2 all-grey, 2 all-yellow
+        let bins = histogram(&[(0, 4.0), (242, 2.0), (121, 2.0)]);
+
+        assert_eq!(Entropy.score(&bins, 8.0), 1.5);
+        assert_eq!(Minimax.score(&bins, 8.0), 4.0);
+        assert_eq!(ExpectedSize.score(&bins, 8.0), 3.0);
+        assert_eq!(MostBuckets.score(&bins, 8.0), 3.0);
+        assert_eq!(Frequency.score(&bins, 8.0), 6.25);
+    }
+
+    // letter frequencies give the same scores as the feedback histograms
+    #[test]
+    fn frequency_without_backend() {
+        let dictionary = Dictionary::bundled();
+        let bytes = dictionary.bytes();
+        let candidates = &dictionary.words()[..500];
+        let weights: Vec<f32> = (0..candidates.len())
+            .map(|i| (i % 7) as f32 + 1.0)
+            .collect();
+        let total: f32 = weights.iter().sum();
+
+        let guesses: Vec<usize> = ["sissy", "geese", "llama", "tares", "eerie"]
+            .iter()
+            .map(|w| dictionary.index_of(w).unwrap())
+            .collect();
+        let guess_words: Vec<Word> = guesses.iter().map(|&g| dictionary.words()[g]).collect();
+        let guess_bytes: Vec<WordBytes> = guesses.iter().map(|&g| bytes[g]).collect();
+
+        let histograms = CpuBackend.histograms(&guess_words, candidates, &weights);
+        let scores = Frequency
+            .score_words(&guess_bytes, &bytes[..500], &weights)
+            .unwrap();
+
+        for (bins, score) in histograms.iter().zip(scores) {
+            assert!((Frequency.score(bins, total) - score).abs() < 1e-3);
+        }
+        assert_eq!(Entropy.score_words(&guess_bytes, bytes, &weights), None);
+    }
+
+    #[test]
+    fn compare_by_goal() {
+        assert_eq!(Entropy.compare(2.0, 1.0), Ordering::Less);
+        assert_eq!(Minimax.compare(2.0, 1.0), Ordering::Greater);
+        assert_eq!(ExpectedSize.compare(1.0, 1.0), Ordering::Equal);
+    }
+
+    #[test]
+    fn kinds_build() {
+        for (kind, name) in [
+            (StrategyKind::Entropy, "entropy"),
+            (StrategyKind::Minimax, "minimax"),
+            (StrategyKind::ExpectedSize, "expected-size"),
+            (StrategyKind::MostBuckets, "most-buckets"),
+            (StrategyKind::Frequency, "frequency"),
+        ] {
+            assert_eq!(kind.build().name(), name);
+            assert_eq!(
+                serde_json::to_string(&kind).unwrap(),
+                format!("\"{}\"", name)
+            );
+        }
+    }
+
+    #[test]
+    fn top_k_ties_by_word() {
+        let words: Vec<_> = ["cigar", "rebut", "sissy", "awake", "tares"]
+            .iter()
+            .map(|w| wordbytes_from_str(w))
+            .collect();
+        let scores = [0.0, 2.0, 1.0, 2.0, 3.0];
+
+        assert_eq!(top_k(&Entropy, &scores, &words, 3), vec![4, 3, 1]);
+        assert_eq!(top_k(&Minimax, &scores, &words, 2), vec![0, 2]);
+        assert_eq!(top_k(&Entropy, &scores, &words, 10), vec![4, 3, 1, 2, 0]);
+        assert_eq!(top_k(&Entropy, &scores, &words, 0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn proptest_top_k_is_sorted_prefix() {
+        proptest!(|(scores in prop::collection::vec(0u8..8, 1..64), k in 0usize..70)| {
+            let scores: Vec<f32> = scores.iter().map(|&s| s as f32).collect();
+            let words: Vec<_> = (0..scores.len())
+                .map(|i| wordbytes_from_str(&format!("{:05}", scores.len() - i)))
+                .collect();
+
+            let mut expected: Vec<usize> = (0..scores.len()).collect();
+            expected.sort_by(|&a, &b| {
+                scores[b].partial_cmp(&scores[a]).unwrap().then(words[a].cmp(&words[b]))
+            });
+            expected.truncate(k);
+
+            assert_eq!(top_k(&Entropy, &scores, &words, k), expected);
+        });
+    }
+}
@@ -0,0 +1,492 @@
+use std::io::Stdout;
+
+use crossterm::cursor::Show;
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    MouseEventKind,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use tui_rs::backend::{Backend, CrosstermBackend};
+use tui_rs::layout::{Constraint, Direction, Layout, Rect};
+use tui_rs::style::{Color, Modifier, Style};
+use tui_rs::text::{Span, Spans};
+use tui_rs::widgets::{Block, Borders, List, ListItem, Paragraph};
+use tui_rs::{Frame, Terminal};
+
+use crate::consistency::{check_row, suggest_fix};
+use crate::fivegram::FIVEGRAM;
+use crate::report::GuessReport;
+use crate::solver::SolverError;
+use crate::{Feedback, Solver, Tile};
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+const HELP: &str = "type a word, 1-5/space/click cycle tiles, enter submits, \
+                    tab takes the best guess, ctrl-z undoes, esc quits";
+/**
+    Tile is 3 cells wide with a gap of one after it
+**/
+const TILE_WIDTH: u16 = 4;
+
+/**
+    Best colour every letter got so far, `None` for the letters not guessed yet
+**/
+pub fn letter_states(solver: &Solver) -> [Option<Tile>; 26] {
+    let mut states = [None; 26];
+
+    for &(guess, feedback) in solver.history() {
+        let bytes = solver.dictionary().bytes()[guess];
+        for (&letter, tile) in bytes.iter().zip(feedback.tiles()) {
+            let state = &mut states[(letter - b'a') as usize];
+            *state = match (*state, tile) {
+                (Some(Tile::Green), _) | (_, Tile::Green) => Some(Tile::Green),
+                (Some(Tile::Yellow), _) | (_, Tile::Yellow) => Some(Tile::Yellow),
+                _ => Some(Tile::Grey),
+            };
+        }
+    }
+
+    states
+}
+
+fn next_tile(tile: Tile) -> Tile {
+    match tile {
+        Tile::Grey => Tile::Yellow,
+        Tile::Yellow => Tile::Green,
+        Tile::Green => Tile::Grey,
+    }
+}
+
+fn tile_style(tile: Option<Tile>) -> Style {
+    let style = Style::default().add_modifier(Modifier::BOLD);
+
+    match tile {
+        Some(Tile::Green) => style.fg(Color::Black).bg(Color::Green),
+        Some(Tile::Yellow) => style.fg(Color::Black).bg(Color::Yellow),
+        Some(Tile::Grey) => style.fg(Color::White).bg(Color::DarkGray),
+        None => style,
+    }
+}
+
+fn tile_span(letter: char, style: Style) -> [Span<'static>; 2] {
+    [
+        Span::styled(format!(" {} ", letter.to_ascii_uppercase()), style),
+        Span::raw(" "),
+    ]
+}
+
+/**
+    Board being edited: guesses so far, the row being typed with the tile
+    colours picked for it, and the solver's view of the game
+**/
+struct App {
+    solver: Solver,
+    top: usize,
+    word: String,
+    tiles: [Tile; FIVEGRAM],
+    selected: usize,
+    suggestions: Vec<GuessReport>,
+    message: String,
+    board: Rect,
+}
+
+impl App {
+    fn new(solver: Solver, top: usize) -> Self {
+        let mut app = App {
+            solver,
+            top,
+            word: String::new(),
+            tiles: [Tile::Grey; FIVEGRAM],
+            selected: 0,
+            suggestions: vec![],
+            message: HELP.to_string(),
+            board: Rect::default(),
+        };
+        app.refresh();
+
+        app
+    }
+
+    fn refresh(&mut self) {
+        self.suggestions = match self.solver.candidates().len() {
+            0 => vec![],
+            _ => self.solver.report(self.top),
+        };
+    }
+
+    fn clear_row(&mut self) {
+        self.word.clear();
+        self.tiles = [Tile::Grey; FIVEGRAM];
+        self.selected = 0;
+    }
+
+    fn cycle(&mut self, i: usize) {
+        if i < self.word.len() {
+            self.tiles[i] = next_tile(self.tiles[i]);
+            self.selected = i;
+        }
+    }
+
+    fn submit(&mut self) {
+        if self.word.len() < FIVEGRAM {
+            self.message = format!("need {} letters", FIVEGRAM);
+            return;
+        }
+
+        let feedback = Feedback::from_tiles(self.tiles);
+        let guess = match self.solver.dictionary().index_of(&self.word) {
+            Some(guess) => guess,
+            None => {
+                self.message = SolverError::UnknownWord(self.word.clone()).to_string();
+                return;
+            }
+        };
+        // the row stays for the colours to be fixed
+        if let Some(contradiction) = check_row(&self.solver, guess, feedback) {
+            self.message = match suggest_fix(&self.solver, guess, feedback) {
+                Some(fix) => format!("{}, did you mean {}?", contradiction, fix),
+                None => contradiction.to_string(),
+            };
+            return;
+        }
+
+        self.solver.apply_index(guess, feedback);
+        self.clear_row();
+        self.refresh();
+        self.message = match self.solver.candidates() {
+            _ if feedback.is_solved() => format!("solved in {}", self.solver.history().len()),
+            [] => match self.fix_last() {
+                Some(fix) => format!(
+                    "no dictionary word fits, did you mean {}? undo takes it back",
+                    fix
+                ),
+                None => "no dictionary word fits, check the colours or undo".to_string(),
+            },
+            [answer] => format!("the answer is {}", self.solver.dictionary().word(*answer)),
+            candidates => format!("{} candidates left", candidates.len()),
+        };
+    }
+
+    /**
+        Single tile fix of the last row, looked up from the game before it
+    **/
+    fn fix_last(&mut self) -> Option<Feedback> {
+        let (guess, feedback) = self.solver.undo()?;
+        let fix = suggest_fix(&self.solver, guess, feedback);
+        self.solver.apply_index(guess, feedback);
+
+        fix
+    }
+
+    fn undo(&mut self) {
+        self.message = match self.solver.undo() {
+            Some((guess, feedback)) => {
+                format!(
+                    "undid {} {}",
+                    self.solver.dictionary().word(guess),
+                    feedback
+                )
+            }
+            None => "nothing to undo".to_string(),
+        };
+        self.clear_row();
+        self.refresh();
+    }
+
+    /**
+        `false` once the user asks to quit
+    **/
+    fn key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if ctrl => return false,
+            KeyCode::Char('z') if ctrl => self.undo(),
+            KeyCode::Char(c @ 'a'..='z') if self.word.len() < FIVEGRAM => {
+                self.tiles[self.word.len()] = Tile::Grey;
+                self.word.push(c);
+                self.selected = self.word.len() - 1;
+            }
+            KeyCode::Char(c @ '1'..='5') => self.cycle(c as usize - '1' as usize),
+            KeyCode::Char(' ') | KeyCode::Up => self.cycle(self.selected),
+            KeyCode::Backspace => {
+                self.word.pop();
+                self.selected = self.word.len().saturating_sub(1);
+            }
+            KeyCode::Left => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Right => {
+                self.selected = (self.selected + 1).min(self.word.len().saturating_sub(1))
+            }
+            KeyCode::Tab => {
+                if let Some(best) = self.suggestions.first() {
+                    self.word = best.word.clone();
+                    self.tiles = [Tile::Grey; FIVEGRAM];
+                    self.selected = 0;
+                }
+            }
+            KeyCode::Enter => self.submit(),
+            _ => {}
+        }
+
+        true
+    }
+
+    /**
+        Clicking a tile of the row being edited cycles its colour
+    **/
+    fn click(&mut self, column: u16, row: u16) {
+        let x = self.board.x + 1;
+        let y = self.board.y + 1 + self.solver.history().len() as u16;
+
+        if row == y && column >= x && (column - x) % TILE_WIDTH < TILE_WIDTH - 1 {
+            self.cycle(((column - x) / TILE_WIDTH) as usize);
+        }
+    }
+
+    fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|f| self.draw(f))?;
+
+            match event::read()? {
+                Event::Key(key) => {
+                    if !self.key(key) {
+                        return Ok(());
+                    }
+                }
+                Event::Mouse(mouse) if matches!(mouse.kind, MouseEventKind::Down(_)) => {
+                    self.click(mouse.column, mouse.row)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(TILE_WIDTH * FIVEGRAM as u16 + 2),
+                Constraint::Min(12),
+                Constraint::Length(32),
+            ])
+            .split(f.size());
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(KEYBOARD.len() as u16 + 2),
+            ])
+            .split(columns[0]);
+        let middle = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(columns[1]);
+
+        self.board = left[0];
+        f.render_widget(self.board_widget(), left[0]);
+        f.render_widget(self.keyboard_widget(), left[1]);
+        f.render_widget(self.candidates_widget(middle[0].height), middle[0]);
+        f.render_widget(
+            Paragraph::new(self.message.as_str()).block(Block::default().borders(Borders::ALL)),
+            middle[1],
+        );
+        f.render_widget(self.suggestions_widget(), columns[2]);
+    }
+
+    fn board_widget(&self) -> Paragraph<'static> {
+        let dictionary = self.solver.dictionary();
+        let mut rows: Vec<Spans> = self
+            .solver
+            .history()
+            .iter()
+            .map(|&(guess, feedback)| {
+                let spans = dictionary
+                    .word(guess)
+                    .chars()
+                    .zip(feedback.tiles())
+                    .flat_map(|(letter, tile)| tile_span(letter, tile_style(Some(tile))))
+                    .collect::<Vec<_>>();
+
+                Spans::from(spans)
+            })
+            .collect();
+
+        let editing = (0..FIVEGRAM)
+            .flat_map(|i| match self.word.chars().nth(i) {
+                Some(letter) => {
+                    let mut style = tile_style(Some(self.tiles[i]));
+                    if i == self.selected {
+                        style = style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    tile_span(letter, style)
+                }
+                None => tile_span('_', Style::default()),
+            })
+            .collect::<Vec<_>>();
+        rows.push(Spans::from(editing));
+
+        Paragraph::new(rows).block(Block::default().borders(Borders::ALL).title("Board"))
+    }
+
+    fn keyboard_widget(&self) -> Paragraph<'static> {
+        let states = letter_states(&self.solver);
+        let rows: Vec<Spans> = KEYBOARD
+            .iter()
+            .map(|row| {
+                let spans = row
+                    .chars()
+                    .map(|c| {
+                        let state = states[(c as u8 - b'a') as usize];
+                        Span::styled(c.to_ascii_uppercase().to_string(), tile_style(state))
+                    })
+                    .collect::<Vec<_>>();
+
+                Spans::from(spans)
+            })
+            .collect();
+
+        Paragraph::new(rows).block(Block::default().borders(Borders::ALL))
+    }
+
+    fn candidates_widget(&self, height: u16) -> List<'static> {
+        let candidates = self.solver.candidates();
+        let items: Vec<ListItem> = candidates
+            .iter()
+            .take(height.saturating_sub(2) as usize)
+            .map(|&c| ListItem::new(self.solver.dictionary().word(c)))
+            .collect();
+        let title = format!("Candidates ({})", candidates.len());
+
+        List::new(items).block(Block::default().borders(Borders::ALL).title(title))
+    }
+
+    fn suggestions_widget(&self) -> List<'static> {
+        let items: Vec<ListItem> = self
+            .suggestions
+            .iter()
+            .map(|r| {
+                let marker = if r.possible_answer { "*" } else { " " };
+                ListItem::new(format!(
+                    "{}{} {:>8.4} {:>6.3} bits",
+                    r.word, marker, r.score, r.entropy
+                ))
+            })
+            .collect();
+        let title = format!("Top by {}", self.solver.strategy().name());
+
+        List::new(items).block(Block::default().borders(Borders::ALL).title(title))
+    }
+}
+
+/**
+    Raw mode, the alternate screen and the mouse capture, undone on drop so
+    that an error or a panic in the app doesn't leave the terminal broken
+**/
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> std::io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // best effort, there is nowhere to report the errors on the way out
+        let _ = disable_raw_mode();
+        let _ = execute!(
+            std::io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            Show
+        );
+    }
+}
+
+/**
+    Full-screen board on the alternate screen, the terminal is restored on
+    the way out
+**/
+pub fn run(solver: Solver, top: usize) -> std::io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+    App::new(solver, top).run(&mut terminal)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use tui_rs::layout::Rect;
+
+    use crate::backend::CpuBackend;
+    use crate::tui::{letter_states, App};
+    use crate::{Dictionary, Solver, Tile};
+
+    fn app() -> App {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+
+        App::new(Solver::new(Arc::new(dictionary), Arc::new(CpuBackend)), 3)
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            assert!(app.key(KeyEvent::new(code, KeyModifiers::NONE)));
+        }
+    }
+
+    #[test]
+    fn type_cycle_and_submit() {
+        let mut app = app();
+        assert_eq!(app.suggestions.len(), 3);
+
+        // tares against sorel: BBGGY
+        press(&mut app, "tares33445\n");
+        assert_eq!(app.solver.candidates().len(), 1);
+        assert_eq!(app.message, "the answer is sorel");
+        assert_eq!(app.word, "");
+
+        let states = letter_states(&app.solver);
+        assert_eq!(states[(b'r' - b'a') as usize], Some(Tile::Green));
+        assert_eq!(states[(b's' - b'a') as usize], Some(Tile::Yellow));
+        assert_eq!(states[(b't' - b'a') as usize], Some(Tile::Grey));
+        assert_eq!(states[(b'z' - b'a') as usize], None);
+
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert!(app.key(ctrl_z));
+        assert_eq!(app.solver.candidates().len(), 7);
+        assert!(!app.key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn tab_and_click() {
+        let mut app = app();
+        press(&mut app, "\t");
+        assert_eq!(app.word, app.suggestions[0].word);
+
+        app.board = Rect::new(0, 0, 22, 10);
+        app.click(1 + 4 * 2, 1);
+        app.click(1 + 4 * 2 + 3, 1);
+        assert_eq!(app.tiles[2], Tile::Yellow);
+        assert_eq!(app.tiles[3], Tile::Grey);
+
+        press(&mut app, "xyz");
+        assert_eq!(app.word.len(), 5);
+    }
+}
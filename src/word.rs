@@ -28,16 +28,30 @@ pub struct Word {
 
 impl Word {
     #[cfg(test)]
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Word {
         let bytes = wordbytes_from_str(s);
 
         Word::from_wordbytes(&bytes)
     }
 
+    pub fn to_str(self) -> String {
+        wordbytes_to_str(&self.to_wordbytes())
+    }
+
     pub fn from_wordbytes(wb: &WordBytes) -> Word {
         Word {
             fivegram: Fivegram::from_bytes(wb),
             letters: AsciiBitSet::from_bytes(wb),
         }
     }
+
+    pub fn to_wordbytes(self) -> WordBytes {
+        let mut bytes = [0; FIVEGRAM];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (self.fivegram.word >> (i * FIVEGRAM) & 0b11111) as u8 - 1 + b'a';
+        }
+
+        bytes
+    }
 }
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::{Dictionary, Feedback};
+
+/**
+    Set of dictionary indices, one bit per word
+**/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordSet {
+    blocks: Vec<u64>,
+    len: usize,
+}
+
+impl WordSet {
+    pub fn empty(len: usize) -> Self {
+        WordSet {
+            blocks: vec![0; (len + 63) / 64],
+            len,
+        }
+    }
+
+    /**
+        Every word of the dictionary of `len` words
+    **/
+    pub fn full(len: usize) -> Self {
+        let mut set = WordSet {
+            blocks: vec![u64::MAX; (len + 63) / 64],
+            len,
+        };
+        if len % 64 != 0 {
+            if let Some(last) = set.blocks.last_mut() {
+                *last = (1 << (len % 64)) - 1;
+            }
+        }
+
+        set
+    }
+
+    pub fn from_indices(len: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut set = Self::empty(len);
+        for idx in indices {
+            set.insert(idx);
+        }
+
+        set
+    }
+
+    #[inline]
+    pub fn insert(&mut self, idx: usize) {
+        self.blocks[idx / 64] |= 1 << (idx % 64);
+    }
+
+    #[inline]
+    pub fn contains(&self, idx: usize) -> bool {
+        idx < self.len && self.blocks[idx / 64] >> (idx % 64) & 1 == 1
+    }
+
+    /**
+        Keeps only the words present in both, sets are of the same dictionary
+    **/
+    pub fn intersect_with(&mut self, other: &WordSet) {
+        debug_assert_eq!(self.len, other.len);
+        for (a, b) in self.blocks.iter_mut().zip(&other.blocks) {
+            *a &= b;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.blocks.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|&b| b == 0)
+    }
+
+    /**
+        Indices in the increasing order
+    **/
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().enumerate().flat_map(|(i, &block)| {
+            let mut block = block;
+            std::iter::from_fn(move || {
+                if block == 0 {
+                    return None;
+                }
+                let bit = block.trailing_zeros() as usize;
+                block &= block - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+/**
+    (guess, feedback) sets kept by default, a set of the bundled dictionary
+    takes about 1.6KB
+**/
+pub const FEEDBACK_INDEX_CAPACITY: usize = 4096;
+
+/**
+    Set with the clock tick it was last used at
+**/
+type Entry = (Arc<WordSet>, AtomicU64);
+
+/**
+    Sets of the dictionary words giving a feedback to a guess, one index per
+    dictionary shared by all of its solvers.
+
+    Only the opener rows are indexed: played while every word is still a
+    candidate, their set is the whole dictionary's and the next solver with
+    the same opener takes it as is. Later rows are scored over the
+    candidates left and not kept. The least recently used set goes first
+    when the index is full.
+**/
+#[derive(Debug)]
+pub struct FeedbackIndex {
+    sets: RwLock<HashMap<(usize, Feedback), Entry>>,
+    capacity: usize,
+    clock: AtomicU64,
+}
+
+impl Default for FeedbackIndex {
+    fn default() -> Self {
+        Self::with_capacity(FEEDBACK_INDEX_CAPACITY)
+    }
+}
+
+impl FeedbackIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        FeedbackIndex {
+            sets: RwLock::new(HashMap::new()),
+            capacity,
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    /**
+        Words among the candidates the guess gets the feedback from. A miss
+        only scores the candidates, unless they are the whole dictionary and
+        the set is then kept for the next solver.
+    **/
+    pub fn matching(
+        &self,
+        dictionary: &Dictionary,
+        guess: usize,
+        feedback: Feedback,
+        candidates: &WordSet,
+    ) -> Arc<WordSet> {
+        let tick = self.clock.fetch_add(1, Ordering::Relaxed);
+        if let Some((set, used)) = self.sets.read().unwrap().get(&(guess, feedback)) {
+            used.store(tick, Ordering::Relaxed);
+            return set.clone();
+        }
+
+        let bytes = dictionary.bytes();
+        let set = Arc::new(WordSet::from_indices(
+            dictionary.len(),
+            candidates
+                .iter()
+                .filter(|&w| Feedback::score(&bytes[guess], &bytes[w]) == feedback),
+        ));
+        if candidates.count() < dictionary.len() || self.capacity == 0 {
+            return set;
+        }
+
+        let mut sets = self.sets.write().unwrap();
+        if sets.len() >= self.capacity && !sets.contains_key(&(guess, feedback)) {
+            let oldest = sets
+                .iter()
+                .min_by_key(|(_, (_, used))| used.load(Ordering::Relaxed))
+                .map(|(&key, _)| key);
+            if let Some(oldest) = oldest {
+                sets.remove(&oldest);
+            }
+        }
+
+        sets.entry((guess, feedback))
+            .or_insert((set, AtomicU64::new(tick)))
+            .0
+            .clone()
+    }
+
+    /**
+        Number of the (guess, feedback) sets kept
+    **/
+    pub fn len(&self) -> usize {
+        self.sets.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::collection::btree_set;
+    use proptest::prelude::*;
+
+    use crate::word_set::{FeedbackIndex, WordSet};
+    use crate::{Dictionary, Feedback};
+
+    #[test]
+    fn full_and_empty() {
+        for len in [1, 63, 64, 65, 12972] {
+            assert_eq!(WordSet::full(len).count(), len);
+            assert_eq!(WordSet::full(len).iter().last(), Some(len - 1));
+            assert!(!WordSet::full(len).contains(len));
+            assert!(WordSet::empty(len).is_empty());
+        }
+    }
+
+    #[test]
+    fn proptest_intersection() {
+        proptest!(|(a in btree_set(0..200usize, 0..50), b in btree_set(0..200usize, 0..50))| {
+            let mut set = WordSet::from_indices(200, a.iter().copied());
+            set.intersect_with(&WordSet::from_indices(200, b.iter().copied()));
+
+            let both: Vec<usize> = a.intersection(&b).copied().collect();
+            assert_eq!(set.iter().collect::<Vec<_>>(), both);
+            assert_eq!(set.count(), both.len());
+        });
+    }
+
+    #[test]
+    fn feedback_sets() {
+        let dictionary =
+            Dictionary::parse("cigar\nrebut\nsissy\nhumph\nawake\nsorel\ntares").unwrap();
+        let index = FeedbackIndex::with_capacity(2);
+        let full = WordSet::full(dictionary.len());
+        let idx = |w: &str| dictionary.index_of(w).unwrap();
+        let feedback: Feedback = "BBGGY".parse().unwrap();
+
+        let set = index.matching(&dictionary, idx("tares"), feedback, &full);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![idx("sorel")]);
+        assert_eq!(index.len(), 1);
+
+        // cached
+        index.matching(&dictionary, idx("tares"), feedback, &full);
+        assert_eq!(index.len(), 1);
+
+        // later rows only score the candidates left
+        let some = WordSet::from_indices(dictionary.len(), [idx("cigar"), idx("sorel")]);
+        let set = index.matching(&dictionary, idx("sissy"), "BGBBB".parse().unwrap(), &some);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![idx("cigar")]);
+        assert_eq!(index.len(), 1);
+
+        // the least recently used set goes
+        index.matching(&dictionary, idx("cigar"), feedback, &full);
+        index.matching(&dictionary, idx("tares"), feedback, &full);
+        index.matching(&dictionary, idx("sorel"), feedback, &full);
+        assert_eq!(index.len(), 2);
+        let kept = index.sets.read().unwrap();
+        assert!(kept.contains_key(&(idx("tares"), feedback)));
+        assert!(kept.contains_key(&(idx("sorel"), feedback)));
+    }
+}
@@ -0,0 +1,102 @@
+//! Browser bindings of the solver, CPU (or SIMD128 with the `simd` feature)
+//! backend on a single thread.
+//!
+//! ```js
+//! const solver = new WordleSolver("entropy");
+//! solver.apply("tares", "BBGGY");
+//! solver.suggest(5);
+//! ```
+
+// wasm-bindgen 0.2.79 expands to `()` bodies clippy doesn't like
+#![allow(clippy::unused_unit)]
+
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use wordle_solver::api::{Api, ExplainResponse, ScoreResponse, SuggestResponse};
+use wordle_solver::{BackendKind, Dictionary, Feedback, Solver, StrategyKind};
+
+fn js_error(err: impl ToString) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+fn to_js(value: &impl serde::Serialize) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(js_error)
+}
+
+#[wasm_bindgen]
+pub struct WordleSolver {
+    solver: Solver,
+}
+
+#[wasm_bindgen]
+impl WordleSolver {
+    /**
+        `strategy` is one of `entropy` (default), `minimax`, `expected-size`,
+        `most-buckets` or `frequency`
+    **/
+    #[wasm_bindgen(constructor)]
+    pub fn new(strategy: Option<String>) -> Result<WordleSolver, JsValue> {
+        let strategy: StrategyKind = match strategy {
+            Some(name) => serde_wasm_bindgen::from_value(JsValue::from_str(&name))?,
+            None => StrategyKind::Entropy,
+        };
+        let backend = BackendKind::Auto.build().map_err(js_error)?;
+        let api = Api::new(Arc::new(Dictionary::bundled()), backend);
+
+        Ok(WordleSolver {
+            solver: api.solver(strategy),
+        })
+    }
+
+    pub fn backend(&self) -> String {
+        self.solver.backend().name().to_string()
+    }
+
+    /**
+        Number of the candidates left after the guess
+    **/
+    pub fn apply(&mut self, guess: &str, feedback: &str) -> Result<usize, JsValue> {
+        let feedback: Feedback = feedback.parse().map_err(js_error)?;
+        self.solver.apply(guess, feedback).map_err(js_error)?;
+
+        Ok(self.solver.candidates().len())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.solver.undo().is_some()
+    }
+
+    pub fn candidates(&self) -> Result<JsValue, JsValue> {
+        let words: Vec<String> = self
+            .solver
+            .candidates()
+            .iter()
+            .map(|&c| self.solver.dictionary().word(c))
+            .collect();
+
+        to_js(&words)
+    }
+
+    /**
+        Same as `POST /suggest`: `{candidates, suggestions: [{rank, word, score, ...}]}`
+    **/
+    pub fn suggest(&self, top: usize) -> Result<JsValue, JsValue> {
+        to_js(&SuggestResponse::new(&self.solver, top))
+    }
+
+    pub fn explain(&self, guess: &str) -> Result<JsValue, JsValue> {
+        to_js(&ExplainResponse::new(&self.solver, guess).map_err(js_error)?)
+    }
+}
+
+/**
+    Feedback as `GYB` string
+**/
+#[wasm_bindgen]
+pub fn score(guess: &str, answer: &str) -> Result<String, JsValue> {
+    ScoreResponse::new(guess, answer)
+        .map(|response| response.feedback.to_string())
+        .map_err(js_error)
+}